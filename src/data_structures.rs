@@ -1,5 +1,10 @@
 pub mod hash_lsm_tree;
 pub mod hash_radix_tree;
 pub mod lazy_hollow_heap;
+pub mod persistent_array;
+pub mod persistent_union_find;
+pub mod rollback_union_find;
+pub mod skew_heap;
 pub mod splay_tree;
 pub mod union_find;
+pub mod weighted_union_find;
@@ -0,0 +1,121 @@
+enum Node<T> {
+    Leaf(T),
+    Internal(usize, usize),
+}
+
+/// A version of a [`PersistentArray`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Version(usize);
+
+/// Arena of the versions of an array as balanced binary trees sharing their unchanged nodes,
+/// which is read and written in `O(log n)` time. Every write makes a new version and keeps the
+/// old ones.
+pub struct PersistentArray<T> {
+    nodes: Vec<Node<T>>,
+    len: usize,
+    initial: Version,
+}
+
+impl<T> PersistentArray<T> {
+    pub fn new(values: Vec<T>) -> Self {
+        let len = values.len();
+        let mut ret = Self {
+            nodes: Vec::with_capacity(2 * len),
+            len,
+            initial: Version(0),
+        };
+        let mut values = values.into_iter();
+        ret.initial = Version(ret.build(len, &mut values));
+        ret
+    }
+
+    fn build(&mut self, len: usize, values: &mut impl Iterator<Item = T>) -> usize {
+        let node = if len <= 1 {
+            // an empty array has a dummy root without values
+            match values.next() {
+                Some(value) => Node::Leaf(value),
+                None => Node::Internal(!0, !0),
+            }
+        } else {
+            let left = self.build(len / 2, values);
+            let right = self.build(len - len / 2, values);
+            Node::Internal(left, right)
+        };
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    /// The version with the values given to [`PersistentArray::new`].
+    pub fn initial(&self) -> Version {
+        self.initial
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn get(&self, version: Version, mut i: usize) -> &T {
+        assert!(i < self.len, "Index {} out of range {}", i, self.len);
+        let (mut x, mut len) = (version.0, self.len);
+        loop {
+            match self.nodes[x] {
+                Node::Leaf(ref value) => return value,
+                Node::Internal(left, right) => {
+                    if i < len / 2 {
+                        x = left;
+                        len /= 2;
+                    } else {
+                        x = right;
+                        i -= len / 2;
+                        len -= len / 2;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Makes a new version with the `i`-th value replaced by `value`.
+    pub fn set(&mut self, version: Version, i: usize, value: T) -> Version {
+        assert!(i < self.len, "Index {} out of range {}", i, self.len);
+        Version(self.set_node(version.0, self.len, i, value))
+    }
+
+    fn set_node(&mut self, x: usize, len: usize, i: usize, value: T) -> usize {
+        let node = match self.nodes[x] {
+            Node::Leaf(_) => Node::Leaf(value),
+            Node::Internal(left, right) => {
+                if i < len / 2 {
+                    Node::Internal(self.set_node(left, len / 2, i, value), right)
+                } else {
+                    let right = self.set_node(right, len - len / 2, i - len / 2, value);
+                    Node::Internal(left, right)
+                }
+            }
+        };
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut pa = PersistentArray::new((0..5).collect());
+        let v0 = pa.initial();
+        let v1 = pa.set(v0, 3, 30);
+        let v2 = pa.set(v1, 0, 10);
+        let v3 = pa.set(v1, 4, 40);
+        let values = |v| (0..5).map(|i| *pa.get(v, i)).collect::<Vec<_>>();
+        assert_eq!(values(v0), vec![0, 1, 2, 3, 4]);
+        assert_eq!(values(v1), vec![0, 1, 2, 30, 4]);
+        assert_eq!(values(v2), vec![10, 1, 2, 30, 4]);
+        assert_eq!(values(v3), vec![0, 1, 2, 30, 40]);
+        assert!(PersistentArray::<()>::new(Vec::new()).is_empty());
+    }
+}
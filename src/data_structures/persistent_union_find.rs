@@ -0,0 +1,94 @@
+use crate::data_structures::persistent_array::{PersistentArray, Version};
+
+#[derive(Copy, Clone)]
+struct Node {
+    parent: usize,
+    size: usize,
+}
+
+/// Fully persistent union find by size without path compression on a [`PersistentArray`], in
+/// `O(log^2 n)` time per operation. Every [`PersistentUnionFind::unite`] makes a new version from
+/// any version.
+pub struct PersistentUnionFind {
+    nodes: PersistentArray<Node>,
+}
+
+impl PersistentUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            nodes: PersistentArray::new((0..n).map(|i| Node { parent: i, size: 1 }).collect()),
+        }
+    }
+
+    /// The version with no unions.
+    pub fn initial(&self) -> Version {
+        self.nodes.initial()
+    }
+
+    /// Returns the new version, whether two components are joined and the new root.
+    pub fn unite(&mut self, version: Version, u: usize, v: usize) -> (Version, bool, usize) {
+        let u = self.find(version, u);
+        let v = self.find(version, v);
+        if u == v {
+            return (version, false, u);
+        }
+        let (nu, nv) = (*self.nodes.get(version, u), *self.nodes.get(version, v));
+        let (parent, child) = if nu.size >= nv.size { (u, v) } else { (v, u) };
+        let size = nu.size + nv.size;
+        let version = self.nodes.set(version, child, Node { parent, size: 0 });
+        let version = self.nodes.set(version, parent, Node { parent, size });
+        (version, true, parent)
+    }
+
+    pub fn find(&self, version: Version, mut u: usize) -> usize {
+        loop {
+            let p = self.nodes.get(version, u).parent;
+            if u == p {
+                return u;
+            } else {
+                u = p
+            }
+        }
+    }
+
+    pub fn same(&self, version: Version, u: usize, v: usize) -> bool {
+        self.find(version, u) == self.find(version, v)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn component_len(&self, version: Version, u: usize) -> usize {
+        let u = self.find(version, u);
+        self.nodes.get(version, u).size
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut uf = PersistentUnionFind::new(5);
+        let v0 = uf.initial();
+        let (v1, joined, _) = uf.unite(v0, 2, 3);
+        assert!(joined);
+        let (v2, _, _) = uf.unite(v1, 1, 3);
+        let (v3, _, _) = uf.unite(v1, 0, 4);
+        let (v4, joined, _) = uf.unite(v2, 2, 1);
+        assert!(!joined);
+        assert_eq!(v4, v2);
+        assert!(!uf.same(v0, 2, 3));
+        assert!(uf.same(v1, 2, 3) && !uf.same(v1, 1, 3));
+        assert!(uf.same(v2, 1, 2) && !uf.same(v2, 0, 4));
+        assert!(uf.same(v3, 0, 4) && !uf.same(v3, 1, 2));
+        assert_eq!(uf.component_len(v2, 2), 3);
+        assert_eq!(uf.component_len(v3, 2), 2);
+        assert_eq!(uf.component_len(v0, 2), 1);
+    }
+}
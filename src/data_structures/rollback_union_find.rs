@@ -0,0 +1,174 @@
+use crate::data_structures::weighted_union_find::Group;
+
+struct Node<T> {
+    parent: usize,
+    size: usize,
+    // sum of the values in the subtree
+    sum: T,
+}
+
+/// Union find by size without path compression, so that [`RollbackUnionFind::find`] takes
+/// `O(log n)` time and each union is undone in `O(1)` time.
+///
+/// Each vertex may hold a value of a commutative group, whose sums over the components are kept.
+/// Changes of the values by [`RollbackUnionFind::add`] are not part of the history.
+pub struct RollbackUnionFind<G: Group = ()> {
+    nodes: Vec<Node<G::Value>>,
+    // the roots joined as children
+    history: Vec<usize>,
+    num_components: usize,
+}
+
+impl<G: Group> RollbackUnionFind<G> {
+    pub fn new(n: usize) -> Self {
+        Self::with_values((0..n).map(|_| G::identity()).collect())
+    }
+
+    pub fn with_values(values: Vec<G::Value>) -> Self {
+        Self {
+            num_components: values.len(),
+            nodes: values
+                .into_iter()
+                .enumerate()
+                .map(|(i, sum)| Node {
+                    parent: i,
+                    size: 1,
+                    sum,
+                })
+                .collect(),
+            history: Vec::new(),
+        }
+    }
+
+    pub fn unite(&mut self, u: usize, v: usize) -> (bool, usize) {
+        let mut u = self.find(u);
+        let mut v = self.find(v);
+        if u == v {
+            return (false, u);
+        }
+        if self.nodes[u].size < self.nodes[v].size {
+            std::mem::swap(&mut u, &mut v);
+        }
+        self.nodes[u].size += self.nodes[v].size;
+        self.nodes[u].sum = G::binary_operation(&self.nodes[u].sum, &self.nodes[v].sum);
+        self.nodes[v].parent = u;
+        self.history.push(v);
+        self.num_components -= 1;
+        (true, u)
+    }
+
+    /// The point of the history to [`RollbackUnionFind::rollback`] to.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes the last union, returning whether there was one.
+    pub fn undo(&mut self) -> bool {
+        let v = match self.history.pop() {
+            Some(v) => v,
+            None => return false,
+        };
+        let u = self.nodes[v].parent;
+        self.nodes[u].size -= self.nodes[v].size;
+        self.nodes[u].sum =
+            G::binary_operation(&self.nodes[u].sum, &G::inverse(&self.nodes[v].sum));
+        self.nodes[v].parent = v;
+        self.num_components += 1;
+        true
+    }
+
+    /// Undoes the unions after the snapshot.
+    pub fn rollback(&mut self, snapshot: usize) {
+        assert!(snapshot <= self.history.len(), "Snapshot from the future");
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+
+    pub fn find(&self, mut u: usize) -> usize {
+        while self.nodes[u].parent != u {
+            u = self.nodes[u].parent;
+        }
+        u
+    }
+
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    pub fn component_len(&self, u: usize) -> usize {
+        self.nodes[self.find(u)].size
+    }
+
+    /// Adds `x` to the value of `u`.
+    pub fn add(&mut self, mut u: usize, x: &G::Value) {
+        loop {
+            self.nodes[u].sum = G::binary_operation(&self.nodes[u].sum, x);
+            if self.nodes[u].parent == u {
+                return;
+            }
+            u = self.nodes[u].parent;
+        }
+    }
+
+    /// The sum of the values in the component of `u`.
+    pub fn component_sum(&self, u: usize) -> &G::Value {
+        &self.nodes[self.find(u)].sum
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Add;
+    impl Group for Add {
+        type Value = i64;
+        fn identity() -> i64 {
+            0
+        }
+        fn binary_operation(lhs: &i64, rhs: &i64) -> i64 {
+            lhs + rhs
+        }
+        fn inverse(val: &i64) -> i64 {
+            -val
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut uf = RollbackUnionFind::<Add>::with_values(vec![1, 2, 4, 8, 16]);
+        assert!(uf.unite(0, 1).0);
+        let snapshot = uf.snapshot();
+        assert!(uf.unite(2, 3).0);
+        assert!(uf.unite(1, 3).0);
+        assert!(!uf.unite(0, 2).0);
+        assert_eq!(uf.num_components(), 2);
+        uf.add(3, &32);
+        assert_eq!(*uf.component_sum(0), 47);
+        uf.rollback(snapshot);
+        assert!(!uf.same(1, 3));
+        assert_eq!(uf.num_components(), 4);
+        assert_eq!(*uf.component_sum(0), 3);
+        assert_eq!(*uf.component_sum(2), 4);
+        assert_eq!(*uf.component_sum(3), 40);
+        assert!(uf.undo());
+        assert!(!uf.undo());
+        assert_eq!(uf.component_len(1), 1);
+
+        let mut uf = RollbackUnionFind::<()>::new(3);
+        uf.unite(0, 2);
+        assert!(uf.same(2, 0));
+    }
+}
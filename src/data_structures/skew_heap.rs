@@ -0,0 +1,147 @@
+use std::{mem, ops::Add};
+
+struct Node<K, V> {
+    key: K,
+    value: Option<V>,
+    // added to the keys of the descendants but not yet to those of the children
+    lazy: Option<K>,
+    children: [Option<usize>; 2],
+}
+
+/// A meldable heap owned by a [`SkewHeap`].
+#[derive(Default, Debug)]
+pub struct Heap(Option<usize>);
+impl Heap {
+    pub fn new() -> Self {
+        Self(None)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+/// Arena of skew heaps, which meld in amortized `O(log n)` time and add to all the keys of a heap
+/// lazily in `O(1)` time.
+pub struct SkewHeap<K, V> {
+    nodes: Vec<Node<K, V>>,
+}
+
+impl<K: Ord + Clone + Add<Output = K>, V> SkewHeap<K, V> {
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    pub fn singleton(&mut self, key: K, value: V) -> Heap {
+        self.nodes.push(Node {
+            key,
+            value: Some(value),
+            lazy: None,
+            children: [None, None],
+        });
+        Heap(Some(self.nodes.len() - 1))
+    }
+
+    fn add_node(&mut self, x: usize, delta: K) {
+        let node = &mut self.nodes[x];
+        node.key = node.key.clone() + delta.clone();
+        node.lazy = Some(match node.lazy.take() {
+            Some(lazy) => lazy + delta,
+            None => delta,
+        });
+    }
+
+    fn push_down(&mut self, x: usize) {
+        if let Some(lazy) = self.nodes[x].lazy.take() {
+            let children = self.nodes[x].children;
+            for &c in children.iter().flatten() {
+                self.add_node(c, lazy.clone());
+            }
+        }
+    }
+
+    fn meld_nodes(&mut self, mut a: Option<usize>, mut b: Option<usize>) -> Option<usize> {
+        // the right spine of the result, merged top-down and swapped bottom-up
+        let mut spine = Vec::new();
+        while let (Some(x), Some(y)) = (a, b) {
+            let (x, y) = if self.nodes[y].key < self.nodes[x].key {
+                (y, x)
+            } else {
+                (x, y)
+            };
+            self.push_down(x);
+            spine.push(x);
+            a = self.nodes[x].children[1];
+            b = Some(y);
+        }
+        let mut root = a.or(b);
+        while let Some(x) = spine.pop() {
+            let children = &mut self.nodes[x].children;
+            children[1] = root;
+            children.swap(0, 1);
+            root = Some(x);
+        }
+        root
+    }
+
+    pub fn meld(&mut self, a: Heap, b: Heap) -> Heap {
+        Heap(self.meld_nodes(a.0, b.0))
+    }
+
+    pub fn push(&mut self, heap: &mut Heap, key: K, value: V) {
+        let single = self.singleton(key, value);
+        *heap = self.meld(mem::take(heap), single);
+    }
+
+    pub fn peek(&self, heap: &Heap) -> Option<(&K, &V)> {
+        heap.0
+            .map(|x| (&self.nodes[x].key, self.nodes[x].value.as_ref().unwrap()))
+    }
+
+    pub fn pop(&mut self, heap: &mut Heap) -> Option<(K, V)> {
+        let x = heap.0?;
+        self.push_down(x);
+        let [l, r] = self.nodes[x].children;
+        heap.0 = self.meld_nodes(l, r);
+        let node = &mut self.nodes[x];
+        Some((node.key.clone(), node.value.take().unwrap()))
+    }
+
+    /// Adds `delta` to all the keys in the heap.
+    pub fn add(&mut self, heap: &Heap, delta: K) {
+        if let Some(x) = heap.0 {
+            self.add_node(x, delta);
+        }
+    }
+}
+
+impl<K: Ord + Clone + Add<Output = K>, V> Default for SkewHeap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut sh = SkewHeap::new();
+        let mut a = Heap::new();
+        for &k in &[5, 1, 4] {
+            sh.push(&mut a, k, k * 10);
+        }
+        let mut b = sh.singleton(3, 30);
+        sh.push(&mut b, 2, 20);
+        sh.add(&b, 10);
+        assert_eq!(sh.peek(&b), Some((&12, &20)));
+        let mut h = sh.meld(a, b);
+        sh.add(&h, -1);
+        let mut popped = Vec::new();
+        while let Some(entry) = sh.pop(&mut h) {
+            popped.push(entry);
+        }
+        assert_eq!(popped, vec![(0, 10), (3, 40), (4, 50), (11, 20), (12, 30)]);
+        assert!(h.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
+pub mod euler_tour_tree;
+pub mod link_cut_tree;
+
 use std::{
     cell::{Ref, RefCell},
     mem,
+    ops::{Bound, RangeBounds},
 };
 
 pub trait SeqOps {
@@ -14,6 +18,27 @@ pub trait SeqOps {
     fn binary_operation(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc;
 
     fn reverse(_val: &mut Self::Value, _acc: &mut Self::Acc) {}
+
+    /// Applies the action pending in the product of a node to a child, for [`Lazy`].
+    fn push_to_child(_acc: &Self::Acc, _child_val: &mut Self::Value, _child_acc: &mut Self::Acc) {}
+
+    /// Clears the action pending in the product of a node after it is pushed to the children.
+    fn clear_pending(_acc: &mut Self::Acc) {}
+}
+
+/// Maps acting on the values of [`SeqOps`], applied to ranges lazily by wrapping in [`Lazy`].
+pub trait SeqActs: SeqOps {
+    type Map: Clone;
+
+    fn identity_map() -> Self::Map;
+
+    /// `f` after `g`.
+    fn composition(f: &Self::Map, g: &Self::Map) -> Self::Map;
+
+    fn map_value(f: &Self::Map, val: &mut Self::Value);
+
+    /// Applies `f` to the product of a range, as the product of the mapped values.
+    fn map_acc(f: &Self::Map, acc: &mut Self::Acc);
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -36,6 +61,8 @@ pub struct Node<'a, K, O: SeqOps> {
     l: Option<NodeRef<'a, K, O>>,
     r: Option<NodeRef<'a, K, O>>,
     p: Option<(Dir, NodeRef<'a, K, O>)>,
+    // the parent of the path in a link-cut tree, kept by the root of each splay tree
+    path_parent: Option<NodeRef<'a, K, O>>,
     len: usize,
     rev: bool,
     value: O::Value,
@@ -50,6 +77,7 @@ impl<'a, K, O: SeqOps> Node<'a, K, O> {
             l: None,
             r: None,
             p: None,
+            path_parent: None,
             len: 1,
             rev: false,
             value,
@@ -67,6 +95,14 @@ impl<'a, K, O: SeqOps> Node<'a, K, O> {
         Self::if_present(&self.r, &mut f);
     }
     fn push(&mut self) {
+        let acc = &self.acc;
+        self.each_child(|ch| O::push_to_child(acc, &mut ch.value, &mut ch.acc));
+        O::clear_pending(&mut self.acc);
+        self.push_rev();
+    }
+    /// The part of [`Node::push`] needed for the product of the node, as the pending actions have
+    /// been applied to it.
+    fn push_rev(&mut self) {
         if mem::take(&mut self.rev) {
             mem::swap(&mut self.l, &mut self.r);
             self.each_child(|ch| {
@@ -81,13 +117,13 @@ impl<'a, K, O: SeqOps> Node<'a, K, O> {
         let mut len = 1;
         let mut acc = O::identity();
         Self::if_present(&self.l, |l| {
-            l.push();
+            l.push_rev();
             len += l.len;
             acc = O::binary_operation(&acc, &l.acc);
         });
         acc = O::binary_operation(&acc, &O::value_to_acc(&self.value));
         Self::if_present(&self.r, |r| {
-            r.push();
+            r.push_rev();
             len += r.len;
             acc = O::binary_operation(&acc, &r.acc);
         });
@@ -147,23 +183,26 @@ impl<'a, K, O: SeqOps> Node<'a, K, O> {
 
     fn splay(node_ref: NodeRef<'a, K, O>) {
         loop {
-            let Some((dir, p_ref)) = node_ref.borrow().p else {
+            let Some((_, p_ref)) = node_ref.borrow().p else {
                 node_ref.borrow_mut().push();
                 return;
             };
+            let pp_ref = p_ref.borrow().p.map(|(_, pp_ref)| pp_ref);
+            // pushes may swap the children, so the directions are read after them
+            if let Some(pp_ref) = pp_ref {
+                pp_ref.borrow_mut().push();
+            }
+            p_ref.borrow_mut().push();
+            node_ref.borrow_mut().push();
+            let dir = node_ref.borrow().p.unwrap().0;
 
-            let Some((p_dir, pp_ref)) = p_ref.borrow().p else {
-                p_ref.borrow_mut().push();
-                node_ref.borrow_mut().push();
+            let Some((p_dir, _)) = p_ref.borrow().p else {
                 match dir {
                     Dir::Left => Self::rotate_right(node_ref),
                     Dir::Right => Self::rotate_left(node_ref),
                 }
                 return;
             };
-            pp_ref.borrow_mut().push();
-            p_ref.borrow_mut().push();
-            node_ref.borrow_mut().push();
             match (p_dir, dir) {
                 (Dir::Left, Dir::Left) => {
                     Self::rotate_right(p_ref);
@@ -185,15 +224,21 @@ impl<'a, K, O: SeqOps> Node<'a, K, O> {
         }
     }
 
+    /// Pushes the node before reading its children, as descents from the root should.
+    fn pushed(node_ref: NodeRef<'a, K, O>) -> Ref<'a, Self> {
+        node_ref.borrow_mut().push();
+        node_ref.borrow()
+    }
+
     fn leftmost(mut node_ref: NodeRef<'a, K, O>) -> NodeRef<'a, K, O> {
-        while let Some(l) = node_ref.borrow().l {
+        while let Some(l) = Self::pushed(node_ref).l {
             node_ref = l
         }
         node_ref
     }
 
     fn rightmost(mut node_ref: NodeRef<'a, K, O>) -> NodeRef<'a, K, O> {
-        while let Some(r) = node_ref.borrow().r {
+        while let Some(r) = Self::pushed(node_ref).r {
             node_ref = r
         }
         node_ref
@@ -282,12 +327,8 @@ pub struct TreeHandle<'arena, K, O: SeqOps>(Option<NodeRef<'arena, K, O>>);
 pub type SeqHandle<'arena, O> = TreeHandle<'arena, (), O>;
 
 impl<'arena, O: SeqOps> SeqHandle<'arena, O> {
-    pub fn concat(TreeHandle(lhs): &Self, TreeHandle(rhs): &Self) -> Self {
-        if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
-            Self(Some(Node::join(lhs, rhs)))
-        } else {
-            Self(lhs.or(*rhs))
-        }
+    pub fn concat(lhs: &Self, rhs: &Self) -> Self {
+        Self::join(lhs, rhs)
     }
 
     pub fn append(&self, other: &Self) {
@@ -300,6 +341,177 @@ impl<'arena, O: SeqOps> SeqHandle<'arena, O> {
             node.borrow_mut().rev ^= true;
         }
     }
+
+    pub fn reverse_range(&self, range: impl RangeBounds<usize>) {
+        let (a, b, c) = self.split_range(range);
+        b.reverse();
+        Self::concat(&Self::concat(&a, &b), &c);
+    }
+
+    /// Splits into the parts before, in and after the range.
+    fn split_range(&self, range: impl RangeBounds<usize>) -> (Self, Self, Self) {
+        let len = self.len();
+        let l = match range.start_bound() {
+            Bound::Included(&l) => l,
+            Bound::Excluded(&l) => l + 1,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(&r) => r + 1,
+            Bound::Excluded(&r) => r,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            l <= r && r <= len,
+            "Range {}..{} out of range {}",
+            l,
+            r,
+            len
+        );
+        let (a, rest) = self.split_by_size(l);
+        let (b, c) = rest.split_by_size(r - l);
+        (a, b, c)
+    }
+
+    pub fn get(&self, i: usize) -> Ref<'arena, O::Value> {
+        Ref::map(self.nth_node(i).borrow(), |node| &node.value)
+    }
+
+    pub fn set(&self, i: usize, value: O::Value) {
+        let mut node = self.nth_node(i).borrow_mut();
+        node.value = value;
+        node.pull();
+    }
+
+    /// Inserts the sequence `other`, typically a singleton, before the `i`-th value.
+    pub fn insert(&mut self, i: usize, other: Self) {
+        let (a, c) = self.split_by_size(i);
+        *self = Self::concat(&Self::concat(&a, &other), &c);
+    }
+
+    /// Removes the `i`-th value, returning it as a singleton.
+    pub fn remove(&mut self, i: usize) -> Self {
+        let (a, b, c) = self.split_range(i..=i);
+        *self = Self::concat(&a, &c);
+        b
+    }
+
+    /// The product of the values in the range.
+    pub fn prod(&self, range: impl RangeBounds<usize>) -> O::Acc
+    where
+        O::Acc: Clone,
+    {
+        let (a, b, c) = self.split_range(range);
+        let product = b.all_product().map_or_else(O::identity, |acc| acc.clone());
+        Self::concat(&Self::concat(&a, &b), &c);
+        product
+    }
+
+    /// The largest `r` such that `f` holds for the product of `l..r`, for `f` holding for the
+    /// identity and monotone like in [`ac_library::Segtree::max_right`].
+    pub fn max_right(&self, l: usize, f: impl Fn(&O::Acc) -> bool) -> usize {
+        assert!(f(&O::identity()));
+        let (a, b) = self.split_by_size(l);
+        let mut r = l;
+        let mut acc = O::identity();
+        let mut node = b.0;
+        let mut last = None;
+        while let Some(x) = node {
+            last = node;
+            let x = Node::pushed(x);
+            let mut l_len = 0;
+            if let Some(l) = x.l {
+                let mut l = l.borrow_mut();
+                l.push_rev();
+                let with_l = O::binary_operation(&acc, &l.acc);
+                if !f(&with_l) {
+                    node = x.l;
+                    continue;
+                }
+                acc = with_l;
+                l_len = l.len;
+            }
+            let with_v = O::binary_operation(&acc, &O::value_to_acc(&x.value));
+            if !f(&with_v) {
+                r += l_len;
+                break;
+            }
+            r += l_len + 1;
+            acc = with_v;
+            node = x.r;
+        }
+        if let Some(last) = last {
+            Node::splay(last);
+        }
+        Self::concat(&a, &Self(last.or(b.0)));
+        r
+    }
+
+    /// The smallest `l` such that `f` holds for the product of `l..r`, for `f` holding for the
+    /// identity and monotone like in [`ac_library::Segtree::min_left`].
+    pub fn min_left(&self, r: usize, f: impl Fn(&O::Acc) -> bool) -> usize {
+        assert!(f(&O::identity()));
+        let (a, b) = self.split_by_size(r);
+        let mut l = r;
+        let mut acc = O::identity();
+        let mut node = a.0;
+        let mut last = None;
+        while let Some(x) = node {
+            last = node;
+            let x = Node::pushed(x);
+            let mut r_len = 0;
+            if let Some(r) = x.r {
+                let mut r = r.borrow_mut();
+                r.push_rev();
+                let with_r = O::binary_operation(&r.acc, &acc);
+                if !f(&with_r) {
+                    node = x.r;
+                    continue;
+                }
+                acc = with_r;
+                r_len = r.len;
+            }
+            let with_v = O::binary_operation(&O::value_to_acc(&x.value), &acc);
+            if !f(&with_v) {
+                l -= r_len;
+                break;
+            }
+            l -= r_len + 1;
+            acc = with_v;
+            node = x.l;
+        }
+        if let Some(last) = last {
+            Node::splay(last);
+        }
+        Self::concat(&Self(last.or(a.0)), &b);
+        l
+    }
+}
+
+/// In-order iterator over the keys and the values of a tree.
+pub struct Iter<'arena, K, O: SeqOps> {
+    // the nodes whose right subtrees are left to visit after them
+    stack: Vec<NodeRef<'arena, K, O>>,
+}
+
+impl<'arena, K, O: SeqOps> Iter<'arena, K, O> {
+    fn descend(&mut self, mut node: Option<NodeRef<'arena, K, O>>) {
+        while let Some(x) = node {
+            self.stack.push(x);
+            node = Node::pushed(x).l;
+        }
+    }
+}
+
+impl<'arena, K, O: SeqOps> Iterator for Iter<'arena, K, O> {
+    type Item = (Ref<'arena, K>, Ref<'arena, O::Value>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.stack.pop()?;
+        let r = x.borrow().r;
+        self.descend(r);
+        Some(Ref::map_split(x.borrow(), |x| (&x.key, &x.value)))
+    }
 }
 
 impl<'arena, K, O: SeqOps> TreeHandle<'arena, K, O> {
@@ -314,6 +526,45 @@ impl<'arena, K, O: SeqOps> TreeHandle<'arena, K, O> {
         self.0.is_none()
     }
 
+    /// Concatenation by position, regardless of the keys.
+    pub(crate) fn join(TreeHandle(lhs): &Self, TreeHandle(rhs): &Self) -> Self {
+        match (lhs, rhs) {
+            (Some(lhs), Some(rhs)) => Self(Some(Node::join(lhs, rhs))),
+            _ => Self(lhs.or(*rhs)),
+        }
+    }
+
+    /// Splays the `i`-th node to the root.
+    fn nth_node(&self, mut i: usize) -> NodeRef<'arena, K, O> {
+        let len = self.len();
+        assert!(i < len, "Index {} out of range {}", i, len);
+        let mut node = self.0.unwrap();
+        loop {
+            let l = Node::if_present(&Node::pushed(node).l, |l| l.len).unwrap_or(0);
+            match i.cmp(&l) {
+                std::cmp::Ordering::Less => node = node.borrow().l.unwrap(),
+                std::cmp::Ordering::Equal => break,
+                std::cmp::Ordering::Greater => {
+                    i -= l + 1;
+                    node = node.borrow().r.unwrap();
+                }
+            }
+        }
+        Node::splay(node);
+        node
+    }
+
+    /// Iterates over the keys and the values in order, which should not be borrowed mutably
+    /// until the iterator is dropped.
+    pub fn iter(&self) -> Iter<'arena, K, O> {
+        let mut iter = Iter { stack: Vec::new() };
+        if let Some(node) = self.0 {
+            Node::splay(node);
+            iter.descend(Some(node));
+        }
+        iter
+    }
+
     pub fn split_by_size(&self, k: usize) -> (Self, Self) {
         let len = self.len();
         assert!(k <= len);
@@ -329,7 +580,7 @@ impl<'arena, K, O: SeqOps> TreeHandle<'arena, K, O> {
         Node::splay(node);
         let mut s = 0;
         let node = loop {
-            let l = Node::if_present(&node.borrow().l, |l| l.len).unwrap_or(0);
+            let l = Node::if_present(&Node::pushed(node).l, |l| l.len).unwrap_or(0);
             match (s + l + 1).cmp(&k) {
                 std::cmp::Ordering::Less => {
                     s += l + 1;
@@ -407,7 +658,7 @@ impl<'arena, K: Ord + Clone, O: SeqOps> TreeHandle<'arena, K, O> {
         };
         Node::splay(node);
         loop {
-            let c = cmp(&node.borrow().key);
+            let c = cmp(&Node::pushed(node).key);
             if c {
                 if let Some(r) = node.borrow().r {
                     node = r;
@@ -453,6 +704,115 @@ impl<'arena, K: Ord + Clone, O: SeqOps> TreeHandle<'arena, K, O> {
             Self(smaller.0.or(larger.0))
         }
     }
+
+    /// The number of the keys satisfying `pred`, which should be a prefix of the keys, and the
+    /// first node with the key not satisfying it splayed to the root.
+    fn partition_point(&self, pred: impl Fn(&K) -> bool) -> (usize, Option<NodeRef<'arena, K, O>>) {
+        let Some(mut node) = self.0 else {
+            return (0, None);
+        };
+        Node::splay(node);
+        let mut count = 0;
+        let mut found = None;
+        loop {
+            let x = Node::pushed(node);
+            let next = if pred(&x.key) {
+                count += Node::if_present(&x.l, |l| l.len).unwrap_or(0) + 1;
+                x.r
+            } else {
+                found = Some(node);
+                x.l
+            };
+            drop(x);
+            match next {
+                Some(next) => node = next,
+                None => break,
+            }
+        }
+        Node::splay(found.unwrap_or(node));
+        (count, found)
+    }
+
+    /// The number of the keys less than `key`.
+    pub fn rank(&self, key: &K) -> usize {
+        self.partition_point(|k| k < key).0
+    }
+
+    /// The number of the keys equal to `key`.
+    pub fn count(&self, key: &K) -> usize {
+        self.partition_point(|k| k <= key).0 - self.rank(key)
+    }
+
+    /// The `i`-th smallest key and its value, where equal keys are in the order of insertion.
+    pub fn nth(&self, i: usize) -> (Ref<'arena, K>, Ref<'arena, O::Value>) {
+        Ref::map_split(self.nth_node(i).borrow(), |node| (&node.key, &node.value))
+    }
+
+    /// The first key not less than `key` and its value.
+    pub fn lower_bound(&self, key: &K) -> Option<(Ref<'arena, K>, Ref<'arena, O::Value>)> {
+        let (_, found) = self.partition_point(|k| k < key);
+        found.map(|node| Ref::map_split(node.borrow(), |node| (&node.key, &node.value)))
+    }
+
+    /// The first key greater than `key` and its value.
+    pub fn upper_bound(&self, key: &K) -> Option<(Ref<'arena, K>, Ref<'arena, O::Value>)> {
+        let (_, found) = self.partition_point(|k| k <= key);
+        found.map(|node| Ref::map_split(node.borrow(), |node| (&node.key, &node.value)))
+    }
+
+    /// The value of the first node with `key`.
+    pub fn get_by_key(&self, key: &K) -> Option<Ref<'arena, O::Value>> {
+        let (_, found) = self.partition_point(|k| k < key);
+        let node = found.filter(|node| node.borrow().key == *key)?;
+        Some(Ref::map(node.borrow(), |node| &node.value))
+    }
+
+    /// Inserts `other`, typically a singleton from [`Trees::singleton_tree`], after the keys not
+    /// greater than its key, so that equal keys are kept as a multiset. Use [`TreeHandle::meld`]
+    /// for trees of many keys.
+    pub fn insert_by_key(&mut self, other: Self) {
+        let key = match other.leftmost() {
+            Some((key, _)) => key.clone(),
+            None => return,
+        };
+        let (a, c) = self.partition_with_key(|k| *k <= key);
+        *self = Self::join(&Self::join(&a, &other), &c);
+    }
+
+    /// Removes the first node with `key`, returning it as a singleton.
+    pub fn remove_by_key(&mut self, key: &K) -> Option<Self> {
+        let (i, found) = self.partition_point(|k| k < key);
+        found.filter(|node| node.borrow().key == *key)?;
+        let (a, rest) = self.split_by_size(i);
+        let (b, c) = rest.split_by_size(1);
+        *self = Self::join(&a, &c);
+        Some(b)
+    }
+
+    /// The product of the values with the keys in the range.
+    pub fn prod_by_key(&self, range: impl RangeBounds<K>) -> O::Acc
+    where
+        O::Acc: Clone,
+    {
+        let l = match range.start_bound() {
+            Bound::Included(l) => self.rank(l),
+            Bound::Excluded(l) => self.partition_point(|k| k <= l).0,
+            Bound::Unbounded => 0,
+        };
+        let r = match range.end_bound() {
+            Bound::Included(r) => self.partition_point(|k| k <= r).0,
+            Bound::Excluded(r) => self.rank(r),
+            Bound::Unbounded => self.len(),
+        };
+        if l >= r {
+            return O::identity();
+        }
+        let (a, rest) = self.split_by_size(l);
+        let (b, c) = rest.split_by_size(r - l);
+        let product = b.all_product().map_or_else(O::identity, |acc| acc.clone());
+        Self::join(&Self::join(&a, &b), &c);
+        product
+    }
 }
 
 pub enum Reversible<O> {
@@ -482,6 +842,26 @@ impl<O: SeqOps> SeqOps for Reversible<O> {
         std::mem::swap(&mut acc.0, &mut acc.1);
     }
 }
+impl<A: SeqActs> SeqActs for Reversible<A> {
+    type Map = A::Map;
+
+    fn identity_map() -> Self::Map {
+        A::identity_map()
+    }
+
+    fn composition(f: &Self::Map, g: &Self::Map) -> Self::Map {
+        A::composition(f, g)
+    }
+
+    fn map_value(f: &Self::Map, val: &mut Self::Value) {
+        A::map_value(f, val);
+    }
+
+    fn map_acc(f: &Self::Map, acc: &mut Self::Acc) {
+        A::map_acc(f, &mut acc.0);
+        A::map_acc(f, &mut acc.1);
+    }
+}
 
 impl<M: ac_library::Monoid> SeqOps for M {
     type Value = <Self as ac_library::Monoid>::S;
@@ -501,6 +881,60 @@ impl<M: ac_library::Monoid> SeqOps for M {
     }
 }
 
+/// [`SeqOps`] with the maps of `A` pending in the products, which act on ranges by
+/// [`SeqHandle::apply`]. The products of `A` are the first of the pairs, and the reversal of `A`
+/// is kept, so `Lazy<Reversible<A>>` is reversible.
+pub enum Lazy<A> {
+    _Phantom(std::marker::PhantomData<A>, std::convert::Infallible),
+}
+impl<A: SeqActs> SeqOps for Lazy<A> {
+    type Value = A::Value;
+
+    type Acc = (A::Acc, A::Map);
+
+    fn value_to_acc(val: &Self::Value) -> Self::Acc {
+        (A::value_to_acc(val), A::identity_map())
+    }
+
+    fn identity() -> Self::Acc {
+        (A::identity(), A::identity_map())
+    }
+
+    fn binary_operation(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc {
+        (A::binary_operation(&lhs.0, &rhs.0), A::identity_map())
+    }
+
+    fn reverse(val: &mut Self::Value, acc: &mut Self::Acc) {
+        A::reverse(val, &mut acc.0);
+    }
+
+    fn push_to_child(acc: &Self::Acc, child_val: &mut Self::Value, child_acc: &mut Self::Acc) {
+        A::map_value(&acc.1, child_val);
+        A::map_acc(&acc.1, &mut child_acc.0);
+        child_acc.1 = A::composition(&acc.1, &child_acc.1);
+    }
+
+    fn clear_pending(acc: &mut Self::Acc) {
+        acc.1 = A::identity_map();
+    }
+}
+
+impl<'arena, A: SeqActs> SeqHandle<'arena, Lazy<A>> {
+    /// Applies `f` to the values in the range lazily.
+    pub fn apply(&self, range: impl RangeBounds<usize>, f: A::Map) {
+        let (a, b, c) = self.split_range(range);
+        if let Some(node) = b.0 {
+            Node::splay(node);
+            let mut node = node.borrow_mut();
+            let node = &mut *node;
+            A::map_value(&f, &mut node.value);
+            A::map_acc(&f, &mut node.acc.0);
+            node.acc.1 = A::composition(&f, &node.acc.1);
+        }
+        Self::concat(&Self::concat(&a, &b), &c);
+    }
+}
+
 #[test]
 fn test() {
     enum Nop {}
@@ -523,4 +957,95 @@ fn test() {
     a.append(&b);
     assert_eq!(a.len(), 2);
     assert_eq!(b.len(), 2);
+
+    // sums with the lengths under x -> b x + c
+    enum Sum {}
+    impl ac_library::Monoid for Sum {
+        type S = (i64, i64);
+        fn identity() -> (i64, i64) {
+            (0, 0)
+        }
+        fn binary_operation(a: &(i64, i64), b: &(i64, i64)) -> (i64, i64) {
+            (a.0 + b.0, a.1 + b.1)
+        }
+    }
+    impl SeqActs for Sum {
+        type Map = (i64, i64);
+        fn identity_map() -> (i64, i64) {
+            (1, 0)
+        }
+        fn composition(&(b, c): &(i64, i64), &(d, e): &(i64, i64)) -> (i64, i64) {
+            (b * d, b * e + c)
+        }
+        fn map_value(&(b, c): &(i64, i64), val: &mut (i64, i64)) {
+            val.0 = b * val.0 + c * val.1;
+        }
+        fn map_acc(f: &(i64, i64), acc: &mut (i64, i64)) {
+            Self::map_value(f, acc);
+        }
+    }
+    let arena = crate::utils::arena::Arena::new();
+    let seqs = Sequences::<Lazy<Sum>>::new(&arena);
+    let seq = seqs.singleton_sequence((0, 1));
+    for i in 1..5 {
+        seq.append(&seqs.singleton_sequence((i, 1)));
+    }
+    seq.apply(1..4, (2, 1));
+    seq.apply(..2, (1, 10));
+    let (l, r) = seq.split_by_size(3);
+    r.reverse();
+    let seq = SeqHandle::concat(&r, &l);
+    seq.apply(1.., (-1, 0));
+    // [10, 13, 5, 7, 4] -> [4, 7, 10, 13, 5] -> [4, -7, -10, -13, -5]
+    assert_eq!(seq.leftmost().map(|(_, v)| *v), Some((4, 1)));
+    assert_eq!(seq.all_product().unwrap().0, (-31, 5));
+    let (l, r) = seq.split_by_size(2);
+    assert_eq!(l.all_product().unwrap().0, (-3, 2));
+    assert_eq!(r.rightmost().map(|(_, v)| *v), Some((-5, 1)));
+
+    let mut seq = SeqHandle::concat(&l, &r);
+    seq.reverse_range(1..4);
+    seq.insert(2, seqs.singleton_sequence((20, 1)));
+    assert_eq!(seq.remove(0).get(0).0, 4);
+    seq.set(0, (30, 1));
+    let values = seq.iter().map(|(_, v)| v.0).collect::<Vec<_>>();
+    assert_eq!(values, vec![30, 20, -10, -7, -5]);
+    assert_eq!(seq.get(3).0, -7);
+    assert_eq!(seq.prod(1..=2).0, (10, 2));
+    assert_eq!(seq.max_right(0, |((sum, _), _)| *sum <= 40), 1);
+    assert_eq!(seq.min_left(4, |((sum, _), _)| *sum <= 0), 2);
+
+    enum Add {}
+    impl ac_library::Monoid for Add {
+        type S = i64;
+        fn identity() -> i64 {
+            0
+        }
+        fn binary_operation(a: &i64, b: &i64) -> i64 {
+            a + b
+        }
+    }
+    let arena = crate::utils::arena::Arena::new();
+    let trees = Trees::<u32, Add>::new(&arena);
+    let mut tree = trees.empty_tree();
+    for (k, v) in [(5, 1), (2, 10), (5, 100), (8, 1000), (2, 10000)] {
+        tree.insert_by_key(trees.singleton_tree(k, v));
+    }
+    assert_eq!(tree.rank(&5), 2);
+    assert_eq!(tree.count(&5), 2);
+    assert_eq!(*tree.get_by_key(&2).unwrap(), 10);
+    assert!(tree.get_by_key(&3).is_none());
+    let (k, v) = tree.nth(1);
+    assert_eq!((*k, *v), (2, 10000));
+    drop((k, v));
+    assert_eq!(tree.lower_bound(&3).map(|(k, _)| *k), Some(5));
+    assert_eq!(tree.upper_bound(&5).map(|(k, _)| *k), Some(8));
+    assert!(tree.upper_bound(&8).is_none());
+    assert_eq!(tree.prod_by_key(2..8), 10111);
+    assert_eq!(tree.prod_by_key(3..), 1101);
+    let removed = tree.remove_by_key(&5).unwrap();
+    assert_eq!(*removed.leftmost().unwrap().1, 1);
+    assert!(tree.remove_by_key(&4).is_none());
+    let keys = tree.iter().map(|(k, _)| *k).collect::<Vec<_>>();
+    assert_eq!(keys, vec![2, 2, 5, 8]);
 }
@@ -0,0 +1,257 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+};
+
+use super::{Node, NodeRef, SeqOps, TreeHandle};
+use crate::utils::arena::Arena;
+
+/// [`SeqOps`] of an Euler tour, where the arcs have no values.
+pub enum EulerTourOps<O> {
+    _Phantom(std::marker::PhantomData<O>, std::convert::Infallible),
+}
+impl<O: SeqOps> SeqOps for EulerTourOps<O> {
+    type Value = Option<O::Value>;
+
+    type Acc = O::Acc;
+
+    fn value_to_acc(val: &Self::Value) -> Self::Acc {
+        val.as_ref().map_or_else(O::identity, O::value_to_acc)
+    }
+
+    fn identity() -> Self::Acc {
+        O::identity()
+    }
+
+    fn binary_operation(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc {
+        O::binary_operation(lhs, rhs)
+    }
+}
+
+// keyed by (u, v) for the arc from u to v and (v, v) for the vertex v
+type EulerTourNodeRef<'arena, O> = NodeRef<'arena, (usize, usize), EulerTourOps<O>>;
+/// Arena of the nodes of an [`EulerTourTree`].
+pub type EulerTourArena<'arena, O> = Arena<RefCell<Node<'arena, (usize, usize), EulerTourOps<O>>>>;
+
+type Tour<'arena, O> = TreeHandle<'arena, (usize, usize), EulerTourOps<O>>;
+
+/// Euler tour trees keeping a forest on the vertices `0..n` under links and cuts in amortized
+/// `O(log n)` time per operation, with the products over the components and subtrees.
+///
+/// The tour of each tree is a sequence of the vertices and the arcs both ways of the edges,
+/// which is rerooted by rotating the sequence. Products are in the order of the tour, so `O`
+/// should be commutative.
+pub struct EulerTourTree<'arena, O: SeqOps> {
+    arena: &'arena EulerTourArena<'arena, O>,
+    vertices: Vec<EulerTourNodeRef<'arena, O>>,
+    arcs: HashMap<(usize, usize), EulerTourNodeRef<'arena, O>>,
+}
+
+impl<'arena, O: SeqOps> EulerTourTree<'arena, O> {
+    pub fn new(
+        arena: &'arena EulerTourArena<'arena, O>,
+        values: impl IntoIterator<Item = O::Value>,
+    ) -> Self {
+        Self {
+            arena,
+            vertices: values
+                .into_iter()
+                .enumerate()
+                .map(|(v, value)| &*arena.allocate(Node::create((v, v), Some(value))))
+                .collect(),
+            arcs: HashMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Index of the node in its sequence.
+    fn position(x: EulerTourNodeRef<'arena, O>) -> usize {
+        Node::splay(x);
+        let l = x.borrow().l;
+        l.map_or(0, |l| l.borrow().len)
+    }
+
+    /// Rotates the tour containing `v` to start from `v`.
+    fn reroot(&self, v: usize) -> Tour<'arena, O> {
+        let x = self.vertices[v];
+        let k = Self::position(x);
+        let (before, after) = TreeHandle(Some(x)).split_by_size(k);
+        Tour::join(&after, &before)
+    }
+
+    /// Adds the edge between `u` and `v`, which should be in different trees.
+    pub fn link(&mut self, u: usize, v: usize) {
+        assert!(!self.connected(u, v), "{} and {} are connected", u, v);
+        let tour_u = self.reroot(u);
+        let tour_v = self.reroot(v);
+        let uv = &*self.arena.allocate(Node::create((u, v), None));
+        let vu = &*self.arena.allocate(Node::create((v, u), None));
+        self.arcs.insert((u, v), uv);
+        self.arcs.insert((v, u), vu);
+        let tour = Tour::join(&tour_v, &TreeHandle(Some(vu)));
+        let tour = Tour::join(&tour, &tour_u);
+        Tour::join(&tour, &TreeHandle(Some(uv)));
+    }
+
+    /// Removes the edge between `u` and `v`, which should exist.
+    pub fn cut(&mut self, u: usize, v: usize) {
+        let (uv, vu) = match (self.arcs.remove(&(u, v)), self.arcs.remove(&(v, u))) {
+            (Some(uv), Some(vu)) => (uv, vu),
+            _ => panic!("No edge between {} and {}", u, v),
+        };
+        let (mut first, mut second) = (uv, vu);
+        if Self::position(first) > Self::position(second) {
+            std::mem::swap(&mut first, &mut second);
+        }
+        // A first B second C into B and A C
+        let k = Self::position(first);
+        let (a, rest) = TreeHandle(Some(first)).split_by_size(k);
+        rest.split_by_size(1);
+        let k = Self::position(second);
+        let (_, rest) = TreeHandle(Some(second)).split_by_size(k);
+        let (_, c) = rest.split_by_size(1);
+        Tour::join(&a, &c);
+    }
+
+    pub fn connected(&self, u: usize, v: usize) -> bool {
+        let (x, y) = (self.vertices[u], self.vertices[v]);
+        Node::splay(x);
+        Node::splay(y);
+        // x is no longer the root if y is in the same splay tree
+        u == v || x.borrow().p.is_some()
+    }
+
+    /// The number of vertices in the tree containing `v`.
+    pub fn component_len(&self, v: usize) -> usize {
+        let x = self.vertices[v];
+        Node::splay(x);
+        // a tree of k vertices has k - 1 edges, each of which appears twice
+        let len = x.borrow().len;
+        len / 3 + 1
+    }
+
+    /// The product of the values in the tree containing `v`.
+    pub fn component_product(&self, v: usize) -> Ref<'arena, O::Acc> {
+        let x = self.vertices[v];
+        Node::splay(x);
+        Ref::map(x.borrow(), |node| &node.acc)
+    }
+
+    /// The product of the values in the subtree of `v` when the tree is rooted at its neighbor
+    /// `parent`.
+    pub fn subtree_product(&self, v: usize, parent: usize) -> O::Acc
+    where
+        O::Acc: Clone,
+    {
+        let (pv, vp) = match (self.arcs.get(&(parent, v)), self.arcs.get(&(v, parent))) {
+            (Some(&pv), Some(&vp)) => (pv, vp),
+            _ => panic!("No edge between {} and {}", v, parent),
+        };
+        self.reroot(parent);
+        // parent A pv B vp C with the subtree in B
+        let k = Self::position(pv);
+        let (a, rest) = TreeHandle(Some(pv)).split_by_size(k + 1);
+        let k = Self::position(vp);
+        let (b, c) = rest.split_by_size(k);
+        let product = b.all_product().map_or_else(O::identity, |acc| acc.clone());
+        let tour = Tour::join(&a, &b);
+        Tour::join(&tour, &c);
+        product
+    }
+
+    /// A vertex in the tree containing `v` whose value satisfies `f`, where `f` should hold for a
+    /// product if and only if it holds for some of the factors, e.g. for flags combined by or.
+    pub fn find_vertex(&self, v: usize, f: impl Fn(&O::Acc) -> bool) -> Option<usize> {
+        let mut x = self.vertices[v];
+        Node::splay(x);
+        if !f(&x.borrow().acc) {
+            return None;
+        }
+        loop {
+            let (l, r, here) = {
+                let node = x.borrow();
+                let here = match node.value {
+                    Some(ref value) => f(&O::value_to_acc(value)),
+                    None => false,
+                };
+                (node.l, node.r, here)
+            };
+            match l {
+                Some(l) if f(&l.borrow().acc) => x = l,
+                _ if here => break,
+                _ => x = r.unwrap(),
+            }
+        }
+        Node::splay(x);
+        let key = x.borrow().key;
+        Some(key.0)
+    }
+
+    pub fn get(&self, v: usize) -> Ref<'arena, O::Value> {
+        Ref::map(self.vertices[v].borrow(), |node| {
+            node.value.as_ref().unwrap()
+        })
+    }
+
+    pub fn set(&self, v: usize, value: O::Value) {
+        let x = self.vertices[v];
+        Node::splay(x);
+        let mut node = x.borrow_mut();
+        node.value = Some(value);
+        node.pull();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    enum Sum {}
+    impl ac_library::Monoid for Sum {
+        type S = i64;
+        fn identity() -> i64 {
+            0
+        }
+        fn binary_operation(a: &i64, b: &i64) -> i64 {
+            a + b
+        }
+    }
+
+    #[test]
+    fn test() {
+        let arena = Arena::new();
+        let mut ett = EulerTourTree::<Sum>::new(&arena, (0..6).map(|i| 1 << i));
+        ett.link(0, 1);
+        ett.link(1, 2);
+        ett.link(3, 1);
+        ett.link(4, 5);
+        assert!(ett.connected(0, 3));
+        assert!(!ett.connected(2, 4));
+        assert_eq!(*ett.component_product(2), 15);
+        assert_eq!(ett.component_len(3), 4);
+        assert_eq!(ett.subtree_product(1, 0), 14);
+        assert_eq!(ett.subtree_product(0, 1), 1);
+        assert_eq!(ett.subtree_product(1, 3), 7);
+        ett.set(2, 100);
+        assert_eq!(*ett.get(2), 100);
+        ett.cut(2, 1);
+        ett.link(2, 4);
+        assert_eq!(*ett.component_product(5), 148);
+        assert_eq!(*ett.component_product(1), 11);
+        ett.cut(0, 1);
+        assert_eq!(ett.component_len(0), 1);
+        assert_eq!(ett.subtree_product(3, 1), 8);
+        assert_eq!(ett.find_vertex(0, |&x| x > 0), Some(0));
+        assert_eq!(ett.find_vertex(0, |&x| x > 1), None);
+        assert!(matches!(
+            ett.find_vertex(4, |&x| x > 0),
+            Some(2) | Some(4) | Some(5)
+        ));
+    }
+}
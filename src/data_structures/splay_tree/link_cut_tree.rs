@@ -0,0 +1,206 @@
+use std::cell::{Ref, RefCell};
+
+use super::{Node, NodeRef, SeqOps};
+use crate::utils::arena::Arena;
+
+/// Link-cut tree on the splay tree nodes, keeping a forest on the vertices `0..n` under links and
+/// cuts in amortized `O(log n)` time per operation.
+///
+/// Each preferred path is a splay tree in the order of depth, with the path parent kept by its
+/// root. Paths are aggregated over `O` in the order from `u` to `v`, so non-commutative
+/// operations should be wrapped in [`Reversible`](super::Reversible) as rerooting reverses paths.
+pub struct LinkCutTree<'arena, O: SeqOps> {
+    nodes: Vec<NodeRef<'arena, usize, O>>,
+}
+
+impl<'arena, O: SeqOps> LinkCutTree<'arena, O> {
+    pub fn new(
+        arena: &'arena Arena<RefCell<Node<'arena, usize, O>>>,
+        values: impl IntoIterator<Item = O::Value>,
+    ) -> Self {
+        Self {
+            nodes: values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| &*arena.allocate(Node::create(i, value)))
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Splays `x` to the root of its splay tree, passing the path parent along.
+    fn splay(x: NodeRef<'arena, usize, O>) {
+        let mut top = x;
+        while let Some((_, p)) = top.borrow().p {
+            top = p;
+        }
+        let path_parent = top.borrow_mut().path_parent.take();
+        Node::splay(x);
+        x.borrow_mut().path_parent = path_parent;
+    }
+
+    fn detach_right(x: NodeRef<'arena, usize, O>) {
+        if let Some(r) = Node::cut_right_child(x) {
+            r.borrow_mut().path_parent = Some(x);
+        }
+    }
+
+    /// Makes the path from the root to `x` preferred with `x` at the root of its splay tree,
+    /// returning the last vertex joined from another path.
+    fn access(&self, v: usize) -> usize {
+        let x = self.nodes[v];
+        Self::splay(x);
+        Self::detach_right(x);
+        let mut last = x;
+        loop {
+            let w = match x.borrow().path_parent {
+                Some(w) => w,
+                None => break,
+            };
+            last = w;
+            Self::splay(w);
+            Self::detach_right(w);
+            Node::link_right_child(w, x);
+            x.borrow_mut().path_parent = None;
+            w.borrow_mut().pull();
+            Self::splay(x);
+        }
+        let key = last.borrow().key;
+        key
+    }
+
+    /// Makes `v` the root of its tree.
+    pub fn evert(&self, v: usize) {
+        self.access(v);
+        self.nodes[v].borrow_mut().rev ^= true;
+    }
+
+    pub fn find_root(&self, v: usize) -> usize {
+        self.access(v);
+        let mut x = self.nodes[v];
+        loop {
+            let mut node = x.borrow_mut();
+            node.push();
+            match node.l {
+                Some(l) => {
+                    drop(node);
+                    x = l;
+                }
+                None => break,
+            }
+        }
+        Self::splay(x);
+        let key = x.borrow().key;
+        key
+    }
+
+    pub fn connected(&self, u: usize, v: usize) -> bool {
+        self.find_root(u) == self.find_root(v)
+    }
+
+    /// Adds the edge between `u` and `v`, which should be in different trees.
+    pub fn link(&self, u: usize, v: usize) {
+        assert!(!self.connected(u, v), "{} and {} are connected", u, v);
+        self.evert(u);
+        self.nodes[u].borrow_mut().path_parent = Some(self.nodes[v]);
+    }
+
+    /// Removes the edge between `u` and `v`, which should exist.
+    pub fn cut(&self, u: usize, v: usize) {
+        self.evert(u);
+        self.access(v);
+        let (x, y) = (self.nodes[u], self.nodes[v]);
+        let adjacent = match y.borrow().l {
+            Some(l) if std::ptr::eq(l, x) => {
+                let mut x = x.borrow_mut();
+                x.push();
+                x.r.is_none()
+            }
+            _ => false,
+        };
+        assert!(adjacent, "No edge between {} and {}", u, v);
+        Node::cut_left_child(y);
+    }
+
+    /// The lowest common ancestor for the current root, or `None` if not connected.
+    pub fn lca(&self, u: usize, v: usize) -> Option<usize> {
+        if !self.connected(u, v) {
+            return None;
+        }
+        self.access(u);
+        Some(self.access(v))
+    }
+
+    /// The product of the values on the path from `u` to `v`, which should be connected. The
+    /// root becomes `u`.
+    pub fn path_product(&self, u: usize, v: usize) -> Ref<'arena, O::Acc> {
+        debug_assert!(self.connected(u, v), "{} and {} are not connected", u, v);
+        self.evert(u);
+        self.access(v);
+        Ref::map(self.nodes[v].borrow(), |node| &node.acc)
+    }
+
+    pub fn get(&self, v: usize) -> Ref<'arena, O::Value> {
+        let x = self.nodes[v];
+        Self::splay(x);
+        Ref::map(x.borrow(), |node| &node.value)
+    }
+
+    pub fn set(&self, v: usize, value: O::Value) {
+        let x = self.nodes[v];
+        Self::splay(x);
+        let mut node = x.borrow_mut();
+        node.value = value;
+        node.pull();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::data_structures::splay_tree::Reversible;
+
+    // affine maps x -> a x + b, applied from left to right
+    enum Affine {}
+    impl ac_library::Monoid for Affine {
+        type S = (i64, i64);
+        fn identity() -> (i64, i64) {
+            (1, 0)
+        }
+        fn binary_operation(f: &(i64, i64), g: &(i64, i64)) -> (i64, i64) {
+            (f.0 * g.0, f.1 * g.0 + g.1)
+        }
+    }
+
+    #[test]
+    fn test() {
+        let arena = Arena::new();
+        let lct = LinkCutTree::<Reversible<Affine>>::new(&arena, (0..6).map(|i| (2, i)));
+        lct.link(0, 1);
+        lct.link(1, 2);
+        lct.link(3, 1);
+        lct.link(4, 5);
+        assert!(lct.connected(0, 3));
+        assert!(!lct.connected(0, 4));
+        lct.evert(2);
+        assert_eq!(lct.lca(0, 3), Some(1));
+        assert_eq!(lct.lca(1, 5), None);
+        // f_0(f_1(f_3(x)))
+        assert_eq!(lct.path_product(3, 0).0, (8, 14));
+        assert_eq!(lct.path_product(0, 3).0, (8, 5));
+        lct.set(1, (1, 10));
+        assert_eq!(*lct.get(1), (1, 10));
+        assert_eq!(lct.path_product(2, 0).0, (4, 24));
+        lct.cut(1, 0);
+        assert!(!lct.connected(0, 2));
+        lct.link(0, 5);
+        assert_eq!(lct.find_root(4), lct.find_root(0));
+        assert_eq!(lct.path_product(4, 0).0, (8, 26));
+    }
+}
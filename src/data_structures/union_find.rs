@@ -1,6 +1,8 @@
 struct Node {
     parent: usize,
     size: usize,
+    // the next member of the component in a circular list
+    next: usize,
 }
 pub struct UnionFind {
     nodes: Vec<Node>,
@@ -9,13 +11,23 @@ pub struct UnionFind {
 impl UnionFind {
     pub fn new(n: usize) -> Self {
         Self {
-            nodes: (0..n).map(|i| Node { parent: i, size: 1 }).collect(),
+            nodes: (0..n)
+                .map(|i| Node {
+                    parent: i,
+                    size: 1,
+                    next: i,
+                })
+                .collect(),
         }
     }
 
     fn join_roots(&mut self, parent: usize, child: usize) {
         self.nodes[parent].size += self.nodes[child].size;
         self.nodes[child].parent = parent;
+        // splices the circular lists
+        let next = self.nodes[parent].next;
+        self.nodes[parent].next = self.nodes[child].next;
+        self.nodes[child].next = next;
     }
 
     pub fn unite(&mut self, u: usize, v: usize) -> (bool, usize) {
@@ -79,6 +91,97 @@ impl UnionFind {
         let u = self.find_mut(u);
         self.nodes[u].size
     }
+
+    /// Members of the component of `u` starting from `u`, in `O(1)` time per member.
+    pub fn members(&self, u: usize) -> Members<'_> {
+        Members {
+            uf: self,
+            start: u,
+            next: Some(u),
+        }
+    }
+
+    /// Members of each component, where the components are in the order of their smallest
+    /// members and each list is sorted.
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups = Vec::new();
+        let mut index = vec![!0; self.len()];
+        for u in 0..self.len() {
+            let r = self.find(u);
+            if index[r] == !0 {
+                index[r] = groups.len();
+                groups.push(Vec::with_capacity(self.nodes[r].size));
+            }
+            groups[index[r]].push(u);
+        }
+        groups
+    }
+}
+
+pub struct Members<'a> {
+    uf: &'a UnionFind,
+    start: usize,
+    next: Option<usize>,
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        let u = self.next?;
+        let next = self.uf.nodes[u].next;
+        self.next = if next == self.start { None } else { Some(next) };
+        Some(u)
+    }
+}
+
+/// [`UnionFind`] with data for each component, which are merged by `merge(parent, child)` on
+/// [`UnionFindWithData::unite`] where `parent` is of the larger component, e.g. for sums or
+/// merging sets from small to large.
+pub struct UnionFindWithData<T, M> {
+    uf: UnionFind,
+    data: Vec<Option<T>>,
+    merge: M,
+}
+
+impl<T, M: FnMut(&mut T, T)> UnionFindWithData<T, M> {
+    pub fn new(values: Vec<T>, merge: M) -> Self {
+        Self {
+            uf: UnionFind::new(values.len()),
+            data: values.into_iter().map(Some).collect(),
+            merge,
+        }
+    }
+
+    pub fn unite(&mut self, u: usize, v: usize) -> (bool, usize) {
+        let u = self.uf.find_mut(u);
+        let v = self.uf.find_mut(v);
+        let (joined, root) = self.uf.unite(u, v);
+        if joined {
+            let child = if root == u { v } else { u };
+            let data = self.data[child].take().unwrap();
+            (self.merge)(self.data[root].as_mut().unwrap(), data);
+        }
+        (joined, root)
+    }
+
+    /// The data of the component of `u`.
+    pub fn get(&self, u: usize) -> &T {
+        self.data[self.uf.find(u)].as_ref().unwrap()
+    }
+
+    pub fn get_mut(&mut self, u: usize) -> &mut T {
+        let u = self.uf.find_mut(u);
+        self.data[u].as_mut().unwrap()
+    }
+
+    /// The underlying union find for the queries not involving data.
+    pub fn union_find(&self) -> &UnionFind {
+        &self.uf
+    }
+
+    pub fn find_mut(&mut self, u: usize) -> usize {
+        self.uf.find_mut(u)
+    }
 }
 
 #[cfg(test)]
@@ -95,5 +198,21 @@ mod test {
         assert!(uf.unite(1, 3).0);
         assert!(uf.same_mut(1, 2));
         assert!(!uf.unite(1, 2).0);
+        let mut members: Vec<_> = uf.members(2).collect();
+        assert_eq!(members[0], 2);
+        members.sort_unstable();
+        assert_eq!(members, vec![1, 2, 3]);
+        assert_eq!(uf.members(4).collect::<Vec<_>>(), vec![4]);
+        assert_eq!(uf.groups(), vec![vec![0], vec![1, 2, 3], vec![4]]);
+
+        let mut uf = UnionFindWithData::new((0..5).map(|i| vec![i]).collect(), |p, c| p.extend(c));
+        uf.unite(0, 3);
+        uf.unite(4, 3);
+        uf.get_mut(1).push(10);
+        let mut data = uf.get(4).clone();
+        data.sort_unstable();
+        assert_eq!(data, vec![0, 3, 4]);
+        assert_eq!(uf.get(1), &vec![1, 10]);
+        assert_eq!(uf.union_find().component_len(0), 3);
     }
 }
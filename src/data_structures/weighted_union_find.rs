@@ -0,0 +1,235 @@
+use std::fmt;
+
+/// A group whose operation may not be commutative.
+pub trait Group {
+    type Value: Clone + PartialEq;
+
+    fn identity() -> Self::Value;
+
+    fn binary_operation(lhs: &Self::Value, rhs: &Self::Value) -> Self::Value;
+
+    fn inverse(val: &Self::Value) -> Self::Value;
+}
+
+/// The trivial group, for no values.
+impl Group for () {
+    type Value = ();
+
+    fn identity() {}
+
+    fn binary_operation(_lhs: &(), _rhs: &()) {}
+
+    fn inverse(_val: &()) {}
+}
+
+/// The relation given to [`WeightedUnionFind::unite`] conflicts with the current difference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Contradiction<T> {
+    pub diff: T,
+}
+impl<T: fmt::Debug> fmt::Display for Contradiction<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "contradicts the current difference {:?}", self.diff)
+    }
+}
+
+struct Node<T> {
+    parent: usize,
+    size: usize,
+    // x_parent^-1 * x_v
+    weight: T,
+}
+
+/// Union find with a value `x_v` of a group for each vertex, where the difference between
+/// vertices is `x_u^-1 * x_v`, i.e. `x_v - x_u` in additive notation.
+pub struct WeightedUnionFind<G: Group> {
+    nodes: Vec<Node<G::Value>>,
+}
+
+impl<G: Group> WeightedUnionFind<G> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            nodes: (0..n)
+                .map(|i| Node {
+                    parent: i,
+                    size: 1,
+                    weight: G::identity(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Requires `x_v = x_u * w`, returning whether two components are joined and the new root,
+    /// or the difference in conflict.
+    pub fn unite(
+        &mut self,
+        u: usize,
+        v: usize,
+        w: G::Value,
+    ) -> Result<(bool, usize), Contradiction<G::Value>> {
+        let (ru, pu) = self.find_mut(u);
+        let (rv, pv) = self.find_mut(v);
+        if ru == rv {
+            let diff = G::binary_operation(&G::inverse(&pu), &pv);
+            return if diff == w {
+                Ok((false, ru))
+            } else {
+                Err(Contradiction { diff })
+            };
+        }
+        // x_ru^-1 * x_rv
+        let between = G::binary_operation(&G::binary_operation(&pu, &w), &G::inverse(&pv));
+        if self.nodes[ru].size >= self.nodes[rv].size {
+            self.join_roots(ru, rv, between);
+            Ok((true, ru))
+        } else {
+            self.join_roots(rv, ru, G::inverse(&between));
+            Ok((true, rv))
+        }
+    }
+
+    fn join_roots(&mut self, parent: usize, child: usize, weight: G::Value) {
+        self.nodes[parent].size += self.nodes[child].size;
+        self.nodes[child].parent = parent;
+        self.nodes[child].weight = weight;
+    }
+
+    /// The root and `x_root^-1 * x_u`.
+    pub fn find_mut(&mut self, mut u: usize) -> (usize, G::Value) {
+        let mut potential = G::identity();
+        while self.nodes[u].parent != u {
+            let p = self.nodes[u].parent;
+            let grand_parent = self.nodes[p].parent;
+            let weight = G::binary_operation(&self.nodes[p].weight, &self.nodes[u].weight);
+            self.nodes[u].parent = grand_parent;
+            potential = G::binary_operation(&weight, &potential);
+            self.nodes[u].weight = weight;
+            u = grand_parent;
+        }
+        (u, potential)
+    }
+
+    /// The root and `x_root^-1 * x_u`.
+    pub fn find(&self, mut u: usize) -> (usize, G::Value) {
+        let mut potential = G::identity();
+        loop {
+            let p = self.nodes[u].parent;
+            if u == p {
+                return (u, potential);
+            } else {
+                potential = G::binary_operation(&self.nodes[u].weight, &potential);
+                u = p
+            }
+        }
+    }
+
+    /// `x_u^-1 * x_v` if `u` and `v` are in the same component.
+    pub fn diff_mut(&mut self, u: usize, v: usize) -> Option<G::Value> {
+        let (ru, pu) = self.find_mut(u);
+        let (rv, pv) = self.find_mut(v);
+        if ru == rv {
+            Some(G::binary_operation(&G::inverse(&pu), &pv))
+        } else {
+            None
+        }
+    }
+
+    /// `x_u^-1 * x_v` if `u` and `v` are in the same component.
+    pub fn diff(&self, u: usize, v: usize) -> Option<G::Value> {
+        let (ru, pu) = self.find(u);
+        let (rv, pv) = self.find(v);
+        if ru == rv {
+            Some(G::binary_operation(&G::inverse(&pu), &pv))
+        } else {
+            None
+        }
+    }
+
+    pub fn same_mut(&mut self, u: usize, v: usize) -> bool {
+        self.find_mut(u).0 == self.find_mut(v).0
+    }
+
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.find(u).0 == self.find(v).0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn component_len(&self, u: usize) -> usize {
+        let u = self.find(u).0;
+        self.nodes[u].size
+    }
+
+    pub fn component_len_mut(&mut self, u: usize) -> usize {
+        let u = self.find_mut(u).0;
+        self.nodes[u].size
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Add;
+    impl Group for Add {
+        type Value = i64;
+        fn identity() -> i64 {
+            0
+        }
+        fn binary_operation(lhs: &i64, rhs: &i64) -> i64 {
+            lhs + rhs
+        }
+        fn inverse(val: &i64) -> i64 {
+            -val
+        }
+    }
+
+    // permutations of 3 elements, where (p * q)[i] = p[q[i]]
+    struct Permutation;
+    impl Group for Permutation {
+        type Value = [usize; 3];
+        fn identity() -> [usize; 3] {
+            [0, 1, 2]
+        }
+        fn binary_operation(lhs: &[usize; 3], rhs: &[usize; 3]) -> [usize; 3] {
+            [lhs[rhs[0]], lhs[rhs[1]], lhs[rhs[2]]]
+        }
+        fn inverse(val: &[usize; 3]) -> [usize; 3] {
+            let mut ret = [0; 3];
+            for (i, &v) in val.iter().enumerate() {
+                ret[v] = i;
+            }
+            ret
+        }
+    }
+
+    #[test]
+    fn test() {
+        let mut uf = WeightedUnionFind::<Add>::new(5);
+        assert_eq!(uf.unite(0, 1, 3), Ok((true, 0)));
+        assert!(uf.unite(2, 1, -2).unwrap().0);
+        assert_eq!(uf.diff(0, 2), Some(5));
+        assert_eq!(uf.diff_mut(2, 0), Some(-5));
+        assert_eq!(uf.diff(0, 3), None);
+        assert_eq!(uf.unite(2, 0, -5), Ok((false, 0)));
+        assert_eq!(uf.unite(2, 0, 1), Err(Contradiction { diff: -5 }));
+        assert_eq!(uf.component_len(1), 3);
+
+        let (a, b) = ([1, 0, 2], [0, 2, 1]);
+        let mut uf = WeightedUnionFind::<Permutation>::new(4);
+        uf.unite(0, 1, a).unwrap();
+        uf.unite(1, 2, b).unwrap();
+        uf.unite(3, 2, a).unwrap();
+        let ab = Permutation::binary_operation(&a, &b);
+        assert_ne!(ab, Permutation::binary_operation(&b, &a));
+        assert_eq!(uf.diff_mut(0, 2), Some(ab));
+        let ba = uf.diff(3, 1).unwrap();
+        assert_eq!(Permutation::binary_operation(&ba, &b), a);
+        assert!(uf.unite(0, 3, [0, 1, 2]).is_err());
+    }
+}
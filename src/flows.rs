@@ -1,6 +1,13 @@
+pub mod convex_cost_flow;
 pub mod dfs_lca_network_simplex;
+pub mod difference_constraints;
 pub mod dinic;
+pub mod disjoint_paths;
+pub mod k_shortest_paths;
+pub mod min_mean_cycle;
+pub mod network;
 pub mod network_simplex;
+pub mod parametric_max_flow;
 
 use std::{
     fmt::Display,
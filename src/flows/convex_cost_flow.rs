@@ -0,0 +1,409 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::flows::{
+    network_simplex::{self, NetworkSimplex},
+    Cost, Flow, Zero,
+};
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct EdgeId(usize);
+
+/// Min cost flow where the cost of an edge is a convex piecewise-linear function of its flow.
+///
+/// Each edge is expanded into parallel edges of [`NetworkSimplex`], one per linear piece, so that
+/// the cheaper pieces are filled first.
+pub struct ConvexCostFlow<F: Flow, C: Cost> {
+    ns: NetworkSimplex<F, C>,
+    edges: Vec<Vec<network_simplex::EdgeId>>,
+    offset: Vec<C>,
+}
+
+pub struct Ret<F, C> {
+    ret: network_simplex::Ret<F, C>,
+    edges: Vec<Vec<network_simplex::EdgeId>>,
+    offset: Vec<C>,
+}
+impl<F: Flow, C: Cost> Ret<F, C> {
+    pub fn get_value<T>(&self) -> T
+    where
+        T: From<F> + From<C> + Mul<Output = T> + Add<Output = T> + Zero,
+    {
+        self.offset
+            .iter()
+            .fold(self.ret.get_value::<T>(), |a, &b| a + T::from(b))
+    }
+    pub fn get_flow(&self, e: &EdgeId) -> F {
+        self.edges[e.0]
+            .iter()
+            .fold(F::zero(), |f, e| f + self.ret.get_flow(e))
+    }
+    pub fn get_potential(&self, v: usize) -> C {
+        self.ret.get_potential(v)
+    }
+}
+
+impl<F: Flow, C: Cost> Default for ConvexCostFlow<F, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Flow, C: Cost> ConvexCostFlow<F, C> {
+    pub fn new() -> Self {
+        Self {
+            ns: NetworkSimplex::new(),
+            edges: Vec::new(),
+            offset: Vec::new(),
+        }
+    }
+
+    pub fn add_supply(&mut self, v: usize, b: F) {
+        self.ns.add_supply(v, b);
+    }
+
+    pub fn add_demand(&mut self, v: usize, b: F) {
+        self.ns.add_demand(v, b);
+    }
+
+    /// Adds an edge whose flow ranges from `lower` to `lower` plus the total length of
+    /// `segments`, where the `i`-th unit costs the slope of the segment it falls in and the flow
+    /// `lower` itself costs nothing.
+    /// Each segment is `(length, slope)` and slopes should be non-decreasing.
+    pub fn add_edge(
+        &mut self,
+        src: usize,
+        dst: usize,
+        lower: F,
+        segments: impl IntoIterator<Item = (F, C)>,
+    ) -> EdgeId {
+        let mut edges = Vec::new();
+        if !lower.is_zero() {
+            edges.push(self.ns.add_edge(src, dst, lower, lower, C::zero()));
+        }
+        let mut last_slope = None;
+        for (length, slope) in segments {
+            assert!(
+                !length.is_negative(),
+                "length {} of a segment should be non-negative",
+                length
+            );
+            if let Some(last_slope) = last_slope {
+                assert!(
+                    last_slope <= slope,
+                    "Cost should be convex, but slope {} follows {}",
+                    slope,
+                    last_slope
+                );
+            }
+            last_slope = Some(slope);
+            if !length.is_zero() {
+                edges.push(self.ns.add_edge(src, dst, F::zero(), length, slope));
+            }
+        }
+        self.edges.push(edges);
+        self.offset.push(C::zero());
+        EdgeId(self.edges.len() - 1)
+    }
+
+    /// Adds an edge of flow in `[lower, upper]` that costs `cost(flow)`, where `cost` should be
+    /// convex on the integers in the range.
+    /// Finds the end of each linear piece by binary search on the slopes, so `cost` is evaluated
+    /// `O(log(upper - lower))` times per piece, and adds one parallel edge per piece.
+    pub fn add_edge_with_cost_function(
+        &mut self,
+        src: usize,
+        dst: usize,
+        lower: F,
+        upper: F,
+        cost: impl Fn(F) -> C,
+    ) -> EdgeId {
+        assert!(
+            lower <= upper,
+            "lower {} should be less or equal to upper {}",
+            lower,
+            upper
+        );
+        // powers of two up to upper - lower, for the binary search
+        let mut powers = Vec::new();
+        if lower < upper {
+            let mut p = F::one();
+            powers.push(p);
+            while p <= upper - lower - p {
+                p += p;
+                powers.push(p);
+            }
+        }
+        let mut segments: Vec<(F, C)> = Vec::new();
+        let base = cost(lower);
+        let mut x = lower;
+        let mut current = base;
+        while x < upper {
+            let slope = cost(x + F::one()) - current;
+            // the units of the same slope are a prefix of the rest by convexity
+            let same = |k: F| cost(x + k + F::one()) - cost(x + k) == slope;
+            let mut length = F::one();
+            for &p in powers.iter().rev() {
+                if p <= upper - x - length && same(length + p - F::one()) {
+                    length += p;
+                }
+            }
+            segments.push((length, slope));
+            x += length;
+            current = cost(x);
+        }
+        let e = self.add_edge(src, dst, lower, segments);
+        self.offset[e.0] = base;
+        e
+    }
+
+    pub fn run(&mut self) -> Option<Ret<F, C>> {
+        let ret = self.ns.run()?;
+        Some(Ret {
+            ret,
+            edges: self.edges.clone(),
+            offset: self.offset.clone(),
+        })
+    }
+}
+
+struct TensionEdge<F, C> {
+    src: usize,
+    dst: usize,
+    slope: F,
+    // the first breakpoint, where `h` vanishes, or zero if there is none
+    anchor: C,
+    breakpoints: Vec<(C, F)>,
+}
+
+impl<F: Flow, C: Cost> TensionEdge<F, C> {
+    fn value<T>(&self, tension: C) -> T
+    where
+        T: From<F> + From<C> + Mul<Output = T> + Add<Output = T> + Zero,
+    {
+        self.breakpoints.iter().filter(|(t, _)| *t < tension).fold(
+            T::from(self.slope) * T::from(tension - self.anchor),
+            |a, &(t, d)| a + T::from(d) * T::from(tension - t),
+        )
+    }
+}
+
+/// Convex cost tension problem, the dual of [`ConvexCostFlow`]: find integer potentials `p`
+/// minimizing
+/// `sum_{(u, v, h)} h(p[v] - p[u]) + sum_v c[v] p[v] + sum_{(v, g)} g(p[v])`
+/// for convex piecewise-linear `h` and `g`.
+///
+/// Each `h` is given as `slope * (x - t_0) + sum_i d_i * max(0, x - t_i)` with `d_i >= 0`,
+/// where `t_0` is the smallest `t_i`, or zero if there is none. The constant `-slope * t_0` does
+/// not change the optimal potentials, so it is left out of the flow and only added back to the
+/// value of the solution. It is solved by the min cost flow where the edge `(u, v)` carries the flow `slope` plus flow in `[0, d_i]`
+/// of cost `t_i` for each `i`, `c[v]` is the supply of `v`, and `g` are edges from an extra
+/// vertex whose potential is fixed to `0`. Optimal potentials of the flow are optimal here.
+pub struct ConvexCostTension<F: Flow, C: Cost> {
+    n: usize,
+    edges: Vec<TensionEdge<F, C>>,
+    linear: Vec<F>,
+}
+
+pub struct TensionRet<F, C> {
+    edges: Vec<TensionEdge<F, C>>,
+    linear: Vec<F>,
+    potential: Vec<C>,
+}
+impl<F: Flow, C: Cost> TensionRet<F, C> {
+    pub fn get_value<T>(&self) -> T
+    where
+        T: From<F> + From<C> + Mul<Output = T> + Add<Output = T> + Sub<Output = T> + Zero,
+    {
+        let p = |v: usize| self.potential.get(v).copied().unwrap_or_else(C::zero);
+        let value = self
+            .edges
+            .iter()
+            .fold(T::zero(), |a, e| a + e.value::<T>(p(e.dst) - p(e.src)));
+        self.linear
+            .iter()
+            .enumerate()
+            .fold(value, |a, (v, &c)| a + T::from(c) * T::from(p(v)))
+    }
+    pub fn get_potential(&self, v: usize) -> C {
+        self.potential[v]
+    }
+    pub fn get_potentials(&self) -> &[C] {
+        &self.potential
+    }
+}
+
+impl<F: Flow, C: Cost> Default for ConvexCostTension<F, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Flow, C: Cost> ConvexCostTension<F, C> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            edges: Vec::new(),
+            linear: Vec::new(),
+        }
+    }
+
+    fn touch(&mut self, v: usize) {
+        self.n = self.n.max(v + 1);
+    }
+
+    /// Adds `h(p[dst] - p[src])` to the objective, where
+    /// `h(x) = slope * (x - t_0) + sum_i d_i * max(0, x - t_i)` for `(t_i, d_i)` in
+    /// `breakpoints` and the smallest `t_0` of them, or `t_0 = 0` if there is none. That is, `h`
+    /// has the slope `slope` below the breakpoints and vanishes at the first one.
+    pub fn add_edge(
+        &mut self,
+        src: usize,
+        dst: usize,
+        slope: F,
+        breakpoints: impl IntoIterator<Item = (C, F)>,
+    ) {
+        self.touch(src);
+        self.touch(dst);
+        self.push_edge(src, dst, slope, breakpoints);
+    }
+
+    /// Adds `g(p[v])` to the objective, where `g` is given as in `add_edge`.
+    pub fn add_vertex_cost(
+        &mut self,
+        v: usize,
+        slope: F,
+        breakpoints: impl IntoIterator<Item = (C, F)>,
+    ) {
+        self.touch(v);
+        self.push_edge(!0, v, slope, breakpoints);
+    }
+
+    /// Adds `c * p[v]` to the objective.
+    pub fn add_linear_cost(&mut self, v: usize, c: F) {
+        self.touch(v);
+        if self.linear.len() <= v {
+            self.linear.resize_with(v + 1, F::zero);
+        }
+        self.linear[v] += c;
+    }
+
+    fn push_edge(
+        &mut self,
+        src: usize,
+        dst: usize,
+        slope: F,
+        breakpoints: impl IntoIterator<Item = (C, F)>,
+    ) {
+        let breakpoints: Vec<_> = breakpoints.into_iter().collect();
+        for &(_, d) in &breakpoints {
+            assert!(
+                !d.is_negative(),
+                "Cost should be convex, but slope changes by {}",
+                d
+            );
+        }
+        let anchor = breakpoints
+            .iter()
+            .map(|&(t, _)| t)
+            .min()
+            .unwrap_or_else(C::zero);
+        self.edges.push(TensionEdge {
+            src,
+            dst,
+            slope,
+            anchor,
+            breakpoints,
+        });
+    }
+
+    /// Returns `None` if the objective is unbounded below.
+    pub fn solve(self) -> Option<TensionRet<F, C>> {
+        let root = self.n;
+        let vertex = |v: usize| if v == !0 { root } else { v };
+        let mut ns = NetworkSimplex::new();
+        // the potential of root is fixed, so it may absorb any imbalance
+        let total = self.linear.iter().fold(F::zero(), |a, &c| a + c);
+        ns.add_demand(root, total);
+        for e in &self.edges {
+            let (src, dst) = (vertex(e.src), vertex(e.dst));
+            if !e.slope.is_zero() {
+                ns.add_edge(src, dst, e.slope, e.slope, C::zero());
+            }
+            for &(t, d) in &e.breakpoints {
+                ns.add_edge(src, dst, F::zero(), d, t);
+            }
+        }
+        for (v, &c) in self.linear.iter().enumerate() {
+            ns.add_supply(v, c);
+        }
+        let ret = ns.run()?;
+        let offset = ret.get_potential(root);
+        let potential = (0..root).map(|v| ret.get_potential(v) - offset).collect();
+        let edges = self
+            .edges
+            .into_iter()
+            .map(|e| TensionEdge {
+                src: vertex(e.src),
+                dst: vertex(e.dst),
+                ..e
+            })
+            .collect();
+        Some(TensionRet {
+            edges,
+            linear: self.linear,
+            potential,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        // 4 units from 0 to 2, where 0 -> 2 costs k^2 and 0 -> 1 -> 2 costs 4 per unit
+        let mut ccf: ConvexCostFlow<i64, i64> = ConvexCostFlow::new();
+        ccf.add_supply(0, 4);
+        ccf.add_demand(2, 4);
+        let direct = ccf.add_edge_with_cost_function(0, 2, 0, 10, |k| k * k);
+        let e01 = ccf.add_edge(0, 1, 0, vec![(10, 1)]);
+        ccf.add_edge(1, 2, 0, vec![(2, 3), (8, 3)]);
+        let ret = ccf.run().unwrap();
+        assert_eq!(ret.get_flow(&direct), 2);
+        assert_eq!(ret.get_flow(&e01), 2);
+        assert_eq!(ret.get_value::<i64>(), 4 + 8);
+
+        // pieces of lengths up to 10^9 are found without going through every unit
+        let mut ccf: ConvexCostFlow<i64, i64> = ConvexCostFlow::new();
+        let calls = std::cell::Cell::new(0);
+        ccf.add_supply(0, 700_000_000);
+        ccf.add_demand(1, 700_000_000);
+        let e = ccf.add_edge_with_cost_function(0, 1, 1, 1_000_000_000, |k| {
+            calls.set(calls.get() + 1);
+            k + 2 * (k - 300_000_000).max(0) + 3 * (k - 999_999_999).max(0)
+        });
+        ccf.add_edge(0, 1, 0, vec![(1_000_000_000, 2)]);
+        assert!(calls.get() < 1000);
+        let ret = ccf.run().unwrap();
+        assert_eq!(ret.get_flow(&e), 300_000_000);
+        assert_eq!(ret.get_value::<i64>(), 300_000_000 + 2 * 400_000_000);
+
+        // minimize |p0 - 3| + |p0 - 5| + |p0 - 10| + 3 |p1 - p0| + 4 (p1 - 7)^+ - 2 p1
+        let mut cct: ConvexCostTension<i64, i64> = ConvexCostTension::new();
+        for &t in &[3, 5, 10] {
+            cct.add_vertex_cost(0, -1, vec![(t, 2)]);
+        }
+        cct.add_edge(0, 1, -3, vec![(0, 6)]);
+        cct.add_vertex_cost(1, 0, vec![(7, 4)]);
+        cct.add_linear_cost(1, -2);
+        let ret = cct.solve().unwrap();
+        assert_eq!(ret.get_potentials(), &[7, 7]);
+        assert_eq!(ret.get_value::<i64>(), -5);
+
+        let mut unbounded: ConvexCostTension<i64, i64> = ConvexCostTension::new();
+        unbounded.add_edge(0, 1, 0, vec![(0, 1)]);
+        unbounded.add_linear_cost(1, 1);
+        assert!(unbounded.solve().is_none());
+    }
+}
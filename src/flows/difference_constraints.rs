@@ -0,0 +1,247 @@
+use std::ops::{Add, Mul};
+
+use crate::flows::{
+    network_simplex::{self, NetworkSimplex},
+    Cost, Flow, Zero,
+};
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct ConstraintId(usize);
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    /// Constraints along a cycle whose bounds sum up to a negative value.
+    Infeasible(Vec<ConstraintId>),
+    /// The objective can be arbitrarily small.
+    Unbounded,
+}
+
+struct Constraint<C> {
+    src: usize,
+    dst: usize,
+    bound: C,
+}
+
+/// Minimizes `sum_v c[v] p[v]` over integer potentials `p` subject to `p[dst] - p[src] <= bound`
+/// and per-vertex bounds.
+///
+/// This is the LP dual of the min cost flow on the edges `src -> dst` of cost `bound` with
+/// unbounded capacity, where `c[v]` is the supply of `v`, and the optimal potentials of
+/// [`NetworkSimplex`] are optimal here. The flow on an edge is the multiplier of the constraint.
+/// Bounds on a vertex are constraints against an extra vertex whose potential is fixed to `0`.
+pub struct DifferenceConstraints<F: Flow, C: Cost> {
+    n: usize,
+    constraints: Vec<Constraint<C>>,
+    coefficients: Vec<F>,
+}
+
+pub struct Ret<F, C> {
+    potential: Vec<C>,
+    coefficients: Vec<F>,
+    multipliers: Vec<F>,
+}
+impl<F: Flow, C: Cost> Ret<F, C> {
+    pub fn get_value<T>(&self) -> T
+    where
+        T: From<F> + From<C> + Mul<Output = T> + Add<Output = T> + Zero,
+    {
+        self.coefficients
+            .iter()
+            .zip(&self.potential)
+            .fold(T::zero(), |a, (&c, &p)| a + T::from(c) * T::from(p))
+    }
+    pub fn get_potential(&self, v: usize) -> C {
+        self.potential[v]
+    }
+    pub fn get_potentials(&self) -> &[C] {
+        &self.potential
+    }
+    /// Optimal dual variable of the constraint, which is positive only if it is tight.
+    pub fn get_multiplier(&self, e: &ConstraintId) -> F {
+        self.multipliers[e.0]
+    }
+}
+
+impl<F: Flow, C: Cost> Default for DifferenceConstraints<F, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Flow, C: Cost> DifferenceConstraints<F, C> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            constraints: Vec::new(),
+            coefficients: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, src: usize, dst: usize, bound: C) -> ConstraintId {
+        self.constraints.push(Constraint { src, dst, bound });
+        ConstraintId(self.constraints.len() - 1)
+    }
+
+    fn touch(&mut self, v: usize) {
+        self.n = self.n.max(v + 1);
+    }
+
+    /// Adds a constraint `p[dst] - p[src] <= bound`.
+    pub fn add_constraint(&mut self, src: usize, dst: usize, bound: C) -> ConstraintId {
+        self.touch(src);
+        self.touch(dst);
+        self.push(src, dst, bound)
+    }
+
+    /// Adds a constraint `lower <= p[v]`.
+    pub fn add_lower_bound(&mut self, v: usize, lower: C) -> ConstraintId {
+        self.touch(v);
+        self.push(v, !0, -lower)
+    }
+
+    /// Adds a constraint `p[v] <= upper`.
+    pub fn add_upper_bound(&mut self, v: usize, upper: C) -> ConstraintId {
+        self.touch(v);
+        self.push(!0, v, upper)
+    }
+
+    /// Adds `c * p[v]` to the objective.
+    pub fn add_objective(&mut self, v: usize, c: F) {
+        self.touch(v);
+        if self.coefficients.len() <= v {
+            self.coefficients.resize_with(v + 1, F::zero);
+        }
+        self.coefficients[v] += c;
+    }
+
+    fn endpoints(&self, e: &Constraint<C>) -> (usize, usize) {
+        let vertex = |v: usize| if v == !0 { self.n } else { v };
+        (vertex(e.src), vertex(e.dst))
+    }
+
+    /// Bellman-Ford from a virtual vertex adjacent to every vertex, returning a negative cycle if
+    /// any.
+    fn find_negative_cycle(&self) -> Option<Vec<ConstraintId>> {
+        let n = self.n + 1;
+        let mut dist = vec![C::zero(); n];
+        let mut parent: Vec<Option<usize>> = vec![None; n];
+        let mut v = 0;
+        for _ in 0..n {
+            let mut updated = false;
+            for (i, e) in self.constraints.iter().enumerate() {
+                let (src, dst) = self.endpoints(e);
+                let d = dist[src] + e.bound;
+                if d < dist[dst] {
+                    dist[dst] = d;
+                    parent[dst] = Some(i);
+                    updated = true;
+                    v = dst;
+                }
+            }
+            if !updated {
+                return None;
+            }
+        }
+        for _ in 0..n {
+            v = self.endpoints(&self.constraints[parent[v].unwrap()]).0;
+        }
+        let start = v;
+        let mut cycle = Vec::new();
+        loop {
+            let e = parent[v].unwrap();
+            cycle.push(ConstraintId(e));
+            v = self.endpoints(&self.constraints[e]).0;
+            if v == start {
+                break;
+            }
+        }
+        cycle.reverse();
+        Some(cycle)
+    }
+
+    pub fn solve(&self) -> Result<Ret<F, C>, Error> {
+        if let Some(cycle) = self.find_negative_cycle() {
+            return Err(Error::Infeasible(cycle));
+        }
+        let root = self.n;
+        let mut coefficients = self.coefficients.clone();
+        coefficients.resize_with(self.n, F::zero);
+
+        // Without negative cycles, every basic solution carries less than the total supply on
+        // each edge, hence this never saturates.
+        let mut total = F::zero();
+        let mut infinity = F::one() + F::one();
+        for &c in &coefficients {
+            total += c;
+            infinity += c.abs() + c.abs();
+        }
+        let mut ns = NetworkSimplex::new();
+        for (v, &c) in coefficients.iter().enumerate() {
+            ns.add_supply(v, c);
+        }
+        ns.add_demand(root, total);
+        let edges: Vec<network_simplex::EdgeId> = self
+            .constraints
+            .iter()
+            .map(|e| {
+                let (src, dst) = self.endpoints(e);
+                ns.add_edge(src, dst, F::zero(), infinity, e.bound)
+            })
+            .collect();
+        let ret = ns.run().ok_or(Error::Unbounded)?;
+        let offset = ret.get_potential(root);
+        Ok(Ret {
+            potential: (0..root).map(|v| ret.get_potential(v) - offset).collect(),
+            coefficients,
+            multipliers: edges.iter().map(|e| ret.get_flow(e)).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        // maximize p2 - p0 subject to p1 - p0 <= 3, p2 - p1 <= 4, p2 - p0 <= 5, 0 <= p0
+        let mut dc: DifferenceConstraints<i32, i32> = DifferenceConstraints::new();
+        dc.add_constraint(0, 1, 3);
+        dc.add_constraint(1, 2, 4);
+        let tight = dc.add_constraint(0, 2, 5);
+        dc.add_lower_bound(0, 0);
+        dc.add_objective(0, 1);
+        dc.add_objective(2, -1);
+        let ret = dc.solve().unwrap();
+        assert_eq!(ret.get_value::<i32>(), -5);
+        assert_eq!(ret.get_multiplier(&tight), 1);
+        let p = ret.get_potentials();
+        assert_eq!(p[2] - p[0], 5);
+        assert!(p[1] - p[0] <= 3 && p[2] - p[1] <= 4);
+
+        // p2 <= 2 is the only thing that bounds p2 now
+        let upper = dc.add_upper_bound(2, 2);
+        dc.add_objective(0, -1);
+        let ret = dc.solve().unwrap();
+        assert_eq!(ret.get_value::<i32>(), -2);
+        assert_eq!(ret.get_potential(2), 2);
+        assert_eq!(ret.get_multiplier(&upper), 1);
+
+        let lower = dc.add_lower_bound(2, 3);
+        match dc.solve() {
+            Err(Error::Infeasible(mut cycle)) => {
+                cycle.sort();
+                assert_eq!(cycle, vec![upper, lower]);
+            }
+            _ => unreachable!(),
+        }
+
+        let mut dc: DifferenceConstraints<i32, i32> = DifferenceConstraints::new();
+        dc.add_constraint(0, 1, 3);
+        dc.add_objective(1, -1);
+        dc.add_objective(0, 1);
+        assert!(dc.solve().is_ok());
+        dc.add_objective(0, 1);
+        assert_eq!(dc.solve().err(), Some(Error::Unbounded));
+    }
+}
@@ -0,0 +1,292 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::flows::Cost;
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct EdgeId(usize);
+
+struct Edge<C> {
+    src: usize,
+    dst: usize,
+    cost: C,
+}
+
+/// Residual arc, whose reverse is at the index `^ 1`.
+struct Arc<C> {
+    dst: usize,
+    capacity: usize,
+    cost: C,
+    // original edge, or !0 for the arcs splitting a vertex
+    edge: usize,
+}
+
+struct TemporaryData<C> {
+    arcs: Vec<Arc<C>>,
+    adjacent_arcs: Vec<Vec<usize>>,
+    potential: Vec<C>,
+}
+
+pub struct Ret<C> {
+    costs: Vec<C>,
+    paths: Vec<Vec<EdgeId>>,
+}
+impl<C: Cost> Ret<C> {
+    /// Number of paths found, which is less than requested if there are no more disjoint paths.
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+    /// Min total cost of `i` disjoint paths, for `i` up to `len()`.
+    pub fn get_cost(&self, i: usize) -> C {
+        if i == 0 {
+            C::zero()
+        } else {
+            self.costs[i - 1]
+        }
+    }
+    /// Edges of the paths attaining `get_cost(len())`.
+    pub fn get_paths(&self) -> &[Vec<EdgeId>] {
+        &self.paths
+    }
+}
+
+/// Min cost disjoint paths by successive shortest paths with Dijkstra on reduced costs, i.e.
+/// Suurballe's algorithm generalized to `k` paths. Costs should be non-negative.
+pub struct DisjointPaths<C: Cost> {
+    n: usize,
+    edges: Vec<Edge<C>>,
+}
+
+impl<C: Cost> Default for DisjointPaths<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Cost> DisjointPaths<C> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_edge(&mut self, src: usize, dst: usize, cost: C) -> EdgeId {
+        assert!(!cost.is_negative(), "cost {} should be non-negative", cost);
+        self.n = self.n.max(src.max(dst) + 1);
+        self.edges.push(Edge { src, dst, cost });
+        EdgeId(self.edges.len() - 1)
+    }
+
+    fn push_arc(
+        data: &mut TemporaryData<C>,
+        src: usize,
+        dst: usize,
+        capacity: usize,
+        cost: C,
+        edge: usize,
+    ) {
+        data.adjacent_arcs[src].push(data.arcs.len());
+        data.arcs.push(Arc {
+            dst,
+            capacity,
+            cost,
+            edge,
+        });
+        data.adjacent_arcs[dst].push(data.arcs.len());
+        data.arcs.push(Arc {
+            dst: src,
+            capacity: 0,
+            cost: -cost,
+            edge,
+        });
+    }
+
+    /// Shortest path from `s` to `t` by reduced costs, updating potentials. Returns the arcs on
+    /// the path in reverse order.
+    fn dijkstra(data: &mut TemporaryData<C>, s: usize, t: usize) -> Option<Vec<usize>> {
+        let n = data.adjacent_arcs.len();
+        let mut dist: Vec<Option<C>> = vec![None; n];
+        let mut parent = vec![!0; n];
+        let mut done = vec![false; n];
+        let mut heap = BinaryHeap::new();
+        dist[s] = Some(C::zero());
+        heap.push(Reverse((C::zero(), s)));
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if done[u] {
+                continue;
+            }
+            done[u] = true;
+            for &a in &data.adjacent_arcs[u] {
+                let arc = &data.arcs[a];
+                if arc.capacity == 0 {
+                    continue;
+                }
+                let v = arc.dst;
+                let nd = d + arc.cost + data.potential[u] - data.potential[v];
+                match dist[v] {
+                    Some(dv) if dv <= nd => {}
+                    _ => {
+                        dist[v] = Some(nd);
+                        parent[v] = a;
+                        heap.push(Reverse((nd, v)));
+                    }
+                }
+            }
+        }
+        let dt = dist[t]?;
+        for (p, d) in data.potential.iter_mut().zip(&dist) {
+            *p += d.unwrap_or(dt).min(dt);
+        }
+        let mut path = Vec::new();
+        let mut v = t;
+        while v != s {
+            let a = parent[v];
+            path.push(a);
+            v = data.arcs[a ^ 1].dst;
+        }
+        Some(path)
+    }
+
+    fn solve(&self, mut data: TemporaryData<C>, s: usize, t: usize, k: usize) -> Ret<C> {
+        assert_ne!(s, t, "Source and sink vertex should be different");
+        let mut costs = Vec::new();
+        let mut total = C::zero();
+        while costs.len() < k {
+            let path = match Self::dijkstra(&mut data, s, t) {
+                Some(path) => path,
+                None => break,
+            };
+            for a in path {
+                data.arcs[a].capacity -= 1;
+                data.arcs[a ^ 1].capacity += 1;
+                total += data.arcs[a].cost;
+            }
+            costs.push(total);
+        }
+
+        // decompose the flow into paths, dropping cycles of cost zero
+        let mut paths = Vec::with_capacity(costs.len());
+        let mut next = vec![0; data.adjacent_arcs.len()];
+        let mut flow: Vec<usize> = (0..data.arcs.len())
+            .map(|a| {
+                if a & 1 == 0 {
+                    data.arcs[a ^ 1].capacity
+                } else {
+                    0
+                }
+            })
+            .collect();
+        for _ in 0..costs.len() {
+            let mut vertices = vec![s];
+            let mut arcs: Vec<usize> = Vec::new();
+            let mut on_path = vec![false; data.adjacent_arcs.len()];
+            on_path[s] = true;
+            let mut v = s;
+            while v != t {
+                let a = loop {
+                    let a = data.adjacent_arcs[v][next[v]];
+                    if flow[a] > 0 {
+                        flow[a] -= 1;
+                        break a;
+                    }
+                    next[v] += 1;
+                };
+                v = data.arcs[a].dst;
+                if on_path[v] {
+                    while *vertices.last().unwrap() != v {
+                        on_path[vertices.pop().unwrap()] = false;
+                        arcs.pop();
+                    }
+                } else {
+                    on_path[v] = true;
+                    vertices.push(v);
+                    arcs.push(a);
+                }
+            }
+            paths.push(
+                arcs.into_iter()
+                    .map(|a| data.arcs[a].edge)
+                    .filter(|&e| e != !0)
+                    .map(EdgeId)
+                    .collect(),
+            );
+        }
+        Ret { costs, paths }
+    }
+
+    fn prepare_data(&self, n: usize) -> TemporaryData<C> {
+        TemporaryData {
+            arcs: Vec::with_capacity(2 * (self.edges.len() + n)),
+            adjacent_arcs: vec![Vec::new(); n],
+            potential: vec![C::zero(); n],
+        }
+    }
+
+    /// Up to `k` edge-disjoint paths from `s` to `t` of the min total cost.
+    pub fn edge_disjoint(&self, s: usize, t: usize, k: usize) -> Ret<C> {
+        let n = self.n.max(s.max(t) + 1);
+        let mut data = self.prepare_data(n);
+        for (i, e) in self.edges.iter().enumerate() {
+            Self::push_arc(&mut data, e.src, e.dst, 1, e.cost, i);
+        }
+        self.solve(data, s, t, k)
+    }
+
+    /// Up to `k` paths from `s` to `t` of the min total cost that share no vertices other than
+    /// `s` and `t`.
+    pub fn vertex_disjoint(&self, s: usize, t: usize, k: usize) -> Ret<C> {
+        // v for entering v, and n + v for leaving v
+        let n = self.n.max(s.max(t) + 1);
+        let mut data = self.prepare_data(2 * n);
+        for v in 0..n {
+            let capacity = if v == s || v == t { k } else { 1 };
+            Self::push_arc(&mut data, v, n + v, capacity, C::zero(), !0);
+        }
+        for (i, e) in self.edges.iter().enumerate() {
+            Self::push_arc(&mut data, n + e.src, e.dst, 1, e.cost, i);
+        }
+        self.solve(data, s, n + t, k)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        // the shortest path 0 -> 1 -> 2 -> 3 shares the middle edge with both disjoint pairs
+        let mut dp = DisjointPaths::new();
+        let e01 = dp.add_edge(0, 1, 1);
+        dp.add_edge(1, 2, 1);
+        let e23 = dp.add_edge(2, 3, 1);
+        let e02 = dp.add_edge(0, 2, 3);
+        let e13 = dp.add_edge(1, 3, 3);
+        let e04 = dp.add_edge(0, 4, 2);
+        dp.add_edge(4, 2, 2);
+
+        let ret = dp.edge_disjoint(0, 3, 3);
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret.get_cost(1), 3);
+        assert_eq!(ret.get_cost(2), 8);
+        let mut paths = ret.get_paths().to_vec();
+        paths.sort();
+        assert_eq!(paths, vec![vec![e01, e13], vec![e02, e23]]);
+
+        // 0 -> 4 -> 2 -> 3 is edge-disjoint from 0 -> 1 -> 3 but not vertex-disjoint from 0 -> 2
+        let ret = dp.vertex_disjoint(0, 3, 3);
+        assert_eq!(ret.len(), 2);
+        assert_eq!(ret.get_cost(2), 8);
+
+        let e43 = dp.add_edge(4, 3, 2);
+        let ret = dp.vertex_disjoint(0, 3, 3);
+        assert_eq!(ret.len(), 3);
+        assert_eq!(ret.get_cost(3), 12);
+        let mut paths = ret.get_paths().to_vec();
+        paths.sort();
+        assert_eq!(paths, vec![vec![e01, e13], vec![e02, e23], vec![e04, e43]]);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap},
+};
+
+use crate::flows::Cost;
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct EdgeId(usize);
+
+struct Edge<C> {
+    src: usize,
+    dst: usize,
+    cost: C,
+}
+
+/// `k` shortest simple paths by Yen's algorithm, in `O(kn(m + n log n))` time.
+/// Costs should be non-negative.
+pub struct KShortestPaths<C: Cost> {
+    n: usize,
+    edges: Vec<Edge<C>>,
+}
+
+impl<C: Cost> Default for KShortestPaths<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Cost> KShortestPaths<C> {
+    pub fn new() -> Self {
+        Self {
+            n: 0,
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_edge(&mut self, src: usize, dst: usize, cost: C) -> EdgeId {
+        assert!(!cost.is_negative(), "cost {} should be non-negative", cost);
+        self.n = self.n.max(src.max(dst) + 1);
+        self.edges.push(Edge { src, dst, cost });
+        EdgeId(self.edges.len() - 1)
+    }
+
+    /// Shortest path from `s` to `t` avoiding the banned vertices and edges.
+    fn dijkstra(
+        &self,
+        out_edges: &[Vec<usize>],
+        s: usize,
+        t: usize,
+        banned_vertices: &[bool],
+        banned_edges: &[bool],
+    ) -> Option<(C, Vec<usize>)> {
+        let n = out_edges.len();
+        let mut dist: Vec<Option<C>> = vec![None; n];
+        let mut parent = vec![!0; n];
+        let mut done = vec![false; n];
+        let mut heap = BinaryHeap::new();
+        dist[s] = Some(C::zero());
+        heap.push(Reverse((C::zero(), s)));
+        while let Some(Reverse((d, u))) = heap.pop() {
+            if done[u] {
+                continue;
+            }
+            done[u] = true;
+            if u == t {
+                break;
+            }
+            for &e in &out_edges[u] {
+                let v = self.edges[e].dst;
+                if banned_edges[e] || banned_vertices[v] {
+                    continue;
+                }
+                let nd = d + self.edges[e].cost;
+                match dist[v] {
+                    Some(dv) if dv <= nd => {}
+                    _ => {
+                        dist[v] = Some(nd);
+                        parent[v] = e;
+                        heap.push(Reverse((nd, v)));
+                    }
+                }
+            }
+        }
+        let d = dist[t]?;
+        let mut path = Vec::new();
+        let mut v = t;
+        while v != s {
+            path.push(parent[v]);
+            v = self.edges[parent[v]].src;
+        }
+        path.reverse();
+        Some((d, path))
+    }
+
+    /// Up to `k` simple paths from `s` to `t` in the non-decreasing order of cost, each with its
+    /// cost and edges.
+    pub fn yen(&self, s: usize, t: usize, k: usize) -> Vec<(C, Vec<EdgeId>)> {
+        let n = self.n.max(s.max(t) + 1);
+        let mut out_edges = vec![Vec::new(); n];
+        for (i, e) in self.edges.iter().enumerate() {
+            out_edges[e.src].push(i);
+        }
+        let mut banned_vertices = vec![false; n];
+        let mut banned_edges = vec![false; self.edges.len()];
+
+        let mut found: Vec<(C, Vec<usize>)> = Vec::new();
+        let mut candidates = BinaryHeap::new();
+        let mut seen = BTreeSet::new();
+        if let Some(path) = self.dijkstra(&out_edges, s, t, &banned_vertices, &banned_edges) {
+            seen.insert(path.1.clone());
+            candidates.push(Reverse(path));
+        }
+        while found.len() < k {
+            let (cost, path) = match candidates.pop() {
+                Some(Reverse(path)) => path,
+                None => break,
+            };
+            // deviate from the path at each vertex on it
+            let mut root_cost = C::zero();
+            let mut spur = s;
+            for i in 0..path.len() {
+                for (_, other) in &found {
+                    if other.len() > i && other[..i] == path[..i] {
+                        banned_edges[other[i]] = true;
+                    }
+                }
+                banned_edges[path[i]] = true;
+                banned_vertices[spur] = true;
+                if let Some((c, spur_path)) =
+                    self.dijkstra(&out_edges, spur, t, &banned_vertices, &banned_edges)
+                {
+                    let mut candidate = path[..i].to_vec();
+                    candidate.extend(spur_path);
+                    if seen.insert(candidate.clone()) {
+                        candidates.push(Reverse((root_cost + c, candidate)));
+                    }
+                }
+                for (_, other) in &found {
+                    if other.len() > i {
+                        banned_edges[other[i]] = false;
+                    }
+                }
+                banned_edges[path[i]] = false;
+                root_cost += self.edges[path[i]].cost;
+                spur = self.edges[path[i]].dst;
+            }
+            for &e in &path {
+                banned_vertices[self.edges[e].src] = false;
+            }
+            found.push((cost, path));
+        }
+        found
+            .into_iter()
+            .map(|(c, path)| (c, path.into_iter().map(EdgeId).collect()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut ksp = KShortestPaths::new();
+        let mut e = Vec::new();
+        for &(u, v, c) in &[
+            (0, 1, 3),
+            (0, 2, 2),
+            (1, 3, 4),
+            (2, 1, 1),
+            (2, 3, 2),
+            (2, 4, 3),
+            (3, 4, 2),
+            (3, 5, 1),
+            (4, 5, 2),
+        ] {
+            e.push(ksp.add_edge(u, v, c));
+        }
+        let paths = ksp.yen(0, 5, 10);
+        let costs: Vec<_> = paths.iter().map(|p| p.0).collect();
+        assert_eq!(costs, vec![5, 7, 8, 8, 8, 11, 11]);
+        assert_eq!(paths[0].1, vec![e[1], e[4], e[7]]);
+        assert_eq!(paths[1].1, vec![e[1], e[5], e[8]]);
+        assert_eq!(ksp.yen(0, 5, 2).len(), 2);
+        assert!(ksp.yen(5, 0, 2).is_empty());
+    }
+}
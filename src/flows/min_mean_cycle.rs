@@ -0,0 +1,344 @@
+use std::cmp::max;
+
+use num::{rational::Ratio, Integer};
+
+use crate::flows::{Cost, One, Zero};
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct EdgeId(usize);
+
+struct Edge<C> {
+    src: usize,
+    dst: usize,
+    cost: C,
+    time: C,
+}
+
+fn num_vertices<C>(edges: &[Edge<C>]) -> usize {
+    edges.iter().fold(0, |n, e| max(n, max(e.src, e.dst) + 1))
+}
+
+/// Cycle of the minimum mean cost.
+pub struct MinMeanCycle<C: Cost + Integer> {
+    edges: Vec<Edge<C>>,
+}
+
+impl<C: Cost + Integer> Default for MinMeanCycle<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Cost + Integer> MinMeanCycle<C> {
+    pub fn new() -> Self {
+        Self { edges: Vec::new() }
+    }
+
+    pub fn add_edge(&mut self, src: usize, dst: usize, cost: C) -> EdgeId {
+        self.edges.push(Edge {
+            src,
+            dst,
+            cost,
+            time: <C as One>::one(),
+        });
+        EdgeId(self.edges.len() - 1)
+    }
+
+    /// Karp's algorithm in `O(nm)` time and `O(n^2)` space.
+    /// Returns the mean and the edges of a cycle in order, or `None` if the graph is acyclic.
+    pub fn karp(&self) -> Option<(Ratio<C>, Vec<EdgeId>)> {
+        let n = num_vertices(&self.edges);
+        // dist[k][v]: min cost of walks of k edges ending at v
+        let mut dist: Vec<Vec<Option<C>>> = vec![vec![Some(<C as Zero>::zero()); n]];
+        let mut parent: Vec<Vec<usize>> = vec![vec![!0; n]];
+        for k in 1..=n {
+            let mut d: Vec<Option<C>> = vec![None; n];
+            let mut p = vec![!0; n];
+            for (i, e) in self.edges.iter().enumerate() {
+                if let Some(du) = dist[k - 1][e.src] {
+                    let candidate = du + e.cost;
+                    match d[e.dst] {
+                        Some(dv) if dv <= candidate => {}
+                        _ => {
+                            d[e.dst] = Some(candidate);
+                            p[e.dst] = i;
+                        }
+                    }
+                }
+            }
+            dist.push(d);
+            parent.push(p);
+        }
+        let mut best: Option<(Ratio<C>, usize)> = None;
+        for (v, dn) in dist[n].iter().enumerate() {
+            let dn = match dn {
+                Some(dn) => *dn,
+                None => continue,
+            };
+            let mut worst: Option<Ratio<C>> = None;
+            let mut len = <C as Zero>::zero();
+            for k in (0..n).rev() {
+                len += <C as One>::one();
+                if let Some(dk) = dist[k][v] {
+                    let mean = Ratio::new(dn - dk, len);
+                    worst = Some(worst.map_or(mean, |w| max(w, mean)));
+                }
+            }
+            let worst = worst.unwrap();
+            match best {
+                Some((b, _)) if b <= worst => {}
+                _ => best = Some((worst, v)),
+            }
+        }
+        let (mean, mut v) = best?;
+        // every cycle on the walk attaining dist[n][v] is a min mean cycle
+        let mut seen = vec![!0; n];
+        let mut k = n;
+        let (from, to) = loop {
+            if seen[v] != !0 {
+                break (k, seen[v]);
+            }
+            seen[v] = k;
+            v = self.edges[parent[k][v]].src;
+            k -= 1;
+        };
+        let mut cycle = Vec::with_capacity(to - from);
+        for k in (from + 1..=to).rev() {
+            let e = parent[k][v];
+            cycle.push(EdgeId(e));
+            v = self.edges[e].src;
+        }
+        cycle.reverse();
+        Some((mean, cycle))
+    }
+
+    /// Howard's policy iteration, usually much faster than [`MinMeanCycle::karp`] though without
+    /// a good bound on the number of iterations.
+    pub fn howard(&self) -> Option<(Ratio<C>, Vec<EdgeId>)> {
+        howard(&self.edges)
+    }
+}
+
+/// Cycle of the minimum ratio of its total cost to its total time.
+/// Times should be non-negative and every cycle should take positive time.
+pub struct MinRatioCycle<C: Cost + Integer> {
+    edges: Vec<Edge<C>>,
+}
+
+impl<C: Cost + Integer> Default for MinRatioCycle<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: Cost + Integer> MinRatioCycle<C> {
+    pub fn new() -> Self {
+        Self { edges: Vec::new() }
+    }
+
+    pub fn add_edge(&mut self, src: usize, dst: usize, cost: C, time: C) -> EdgeId {
+        assert!(
+            time >= <C as Zero>::zero(),
+            "time {} should be non-negative",
+            time
+        );
+        self.edges.push(Edge {
+            src,
+            dst,
+            cost,
+            time,
+        });
+        EdgeId(self.edges.len() - 1)
+    }
+
+    /// Howard's policy iteration with exact arithmetic.
+    /// Returns the ratio and the edges of a cycle in order, or `None` if the graph is acyclic.
+    pub fn solve(&self) -> Option<(Ratio<C>, Vec<EdgeId>)> {
+        howard(&self.edges)
+    }
+}
+
+/// Policy iteration where each vertex chooses an out-edge, and the policy is improved first by
+/// the ratio of the cycle it reaches and then by the cost to reach there.
+///
+/// Costs to reach the cycle of ratio `p / q` are measured by `q * cost - p * time`, which keeps
+/// them integral.
+fn howard<C: Cost + Integer>(edges: &[Edge<C>]) -> Option<(Ratio<C>, Vec<EdgeId>)> {
+    let n = num_vertices(edges);
+    let mut out_edges = vec![Vec::new(); n];
+    let mut in_edges = vec![Vec::new(); n];
+    for (i, e) in edges.iter().enumerate() {
+        out_edges[e.src].push(i);
+        in_edges[e.dst].push(i);
+    }
+
+    // drop vertices that can't reach a cycle
+    let mut alive = vec![true; n];
+    let mut out_degree: Vec<_> = out_edges.iter().map(Vec::len).collect();
+    let mut stack: Vec<_> = (0..n).filter(|&v| out_degree[v] == 0).collect();
+    while let Some(v) = stack.pop() {
+        alive[v] = false;
+        for &e in &in_edges[v] {
+            let u = edges[e].src;
+            out_degree[u] -= 1;
+            if out_degree[u] == 0 {
+                stack.push(u);
+            }
+        }
+    }
+    for out in out_edges.iter_mut() {
+        out.retain(|&e| alive[edges[e].dst]);
+    }
+    let vertices: Vec<_> = (0..n).filter(|&v| alive[v]).collect();
+    if vertices.is_empty() {
+        return None;
+    }
+
+    let mut policy = vec![!0; n];
+    for &v in &vertices {
+        policy[v] = *out_edges[v].iter().min_by_key(|&&e| edges[e].cost).unwrap();
+    }
+    let weight = |e: &Edge<C>, ratio: &Ratio<C>| *ratio.denom() * e.cost - *ratio.numer() * e.time;
+
+    let mut ratio: Vec<Option<Ratio<C>>> = vec![None; n];
+    let mut value = vec![<C as Zero>::zero(); n];
+    loop {
+        // evaluate the policy
+        let mut state = vec![0u8; n];
+        let mut cycles = Vec::new();
+        for &s in &vertices {
+            if state[s] != 0 {
+                continue;
+            }
+            let mut path = Vec::new();
+            let mut v = s;
+            while state[v] == 0 {
+                state[v] = 1;
+                path.push(v);
+                v = edges[policy[v]].dst;
+            }
+            if state[v] == 1 {
+                let start = path.iter().position(|&u| u == v).unwrap();
+                let cycle = path.split_off(start);
+                let (cost, time) = cycle
+                    .iter()
+                    .fold((<C as Zero>::zero(), <C as Zero>::zero()), |(c, t), &u| {
+                        (c + edges[policy[u]].cost, t + edges[policy[u]].time)
+                    });
+                assert!(
+                    time > <C as Zero>::zero(),
+                    "A cycle should take positive time"
+                );
+                let r = Ratio::new(cost, time);
+                let root_pos = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+                let root = cycle[root_pos];
+                value[root] = <C as Zero>::zero();
+                ratio[root] = Some(r);
+                state[root] = 2;
+                for i in (1..cycle.len()).rev() {
+                    let u = cycle[(root_pos + i) % cycle.len()];
+                    let e = &edges[policy[u]];
+                    value[u] = weight(e, &r) + value[e.dst];
+                    ratio[u] = Some(r);
+                    state[u] = 2;
+                }
+                cycles.push((r, root));
+            }
+            for &u in path.iter().rev() {
+                let e = &edges[policy[u]];
+                let r = ratio[e.dst].unwrap();
+                value[u] = weight(e, &r) + value[e.dst];
+                ratio[u] = Some(r);
+                state[u] = 2;
+            }
+        }
+
+        // improve the ratio of the cycle to reach
+        let mut improved = false;
+        for &u in &vertices {
+            let mut best = ratio[u].unwrap();
+            for &e in &out_edges[u] {
+                let r = ratio[edges[e].dst].unwrap();
+                if r < best {
+                    best = r;
+                    policy[u] = e;
+                    improved = true;
+                }
+            }
+        }
+        if improved {
+            continue;
+        }
+        // improve the cost to reach the cycle
+        for &u in &vertices {
+            let r = ratio[u].unwrap();
+            let mut best = value[u];
+            for &e in &out_edges[u] {
+                let e_ref = &edges[e];
+                if ratio[e_ref.dst] != Some(r) {
+                    continue;
+                }
+                let candidate = weight(e_ref, &r) + value[e_ref.dst];
+                if candidate < best {
+                    best = candidate;
+                    policy[u] = e;
+                    improved = true;
+                }
+            }
+        }
+        if improved {
+            continue;
+        }
+
+        let (r, root) = cycles.into_iter().min_by(|a, b| a.0.cmp(&b.0)).unwrap();
+        let mut cycle = Vec::new();
+        let mut v = root;
+        loop {
+            cycle.push(EdgeId(policy[v]));
+            v = edges[policy[v]].dst;
+            if v == root {
+                break;
+            }
+        }
+        return Some((r, cycle));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut mmc = MinMeanCycle::new();
+        let mut mrc = MinRatioCycle::new();
+        let mut edges = Vec::new();
+        for &(u, v, c, t) in &[
+            (0, 1, 2, 1),
+            (1, 0, 2, 1),
+            (1, 2, 1, 1),
+            (2, 3, 2, 2),
+            (3, 1, 0, 3),
+            (3, 4, -5, 1),
+            (5, 3, -100, 1),
+        ] {
+            edges.push(mmc.add_edge(u, v, c));
+            mrc.add_edge(u, v, c, t);
+        }
+        let expected = (Ratio::new(1, 1), vec![edges[2], edges[3], edges[4]]);
+        let (mean, mut cycle) = mmc.karp().unwrap();
+        let start = cycle.iter().position(|e| e == &edges[2]).unwrap();
+        cycle.rotate_left(start);
+        assert_eq!((mean, cycle), expected);
+        assert_eq!(mmc.howard(), Some(expected));
+        assert_eq!(
+            mrc.solve(),
+            Some((Ratio::new(1, 2), vec![edges[2], edges[3], edges[4]]))
+        );
+
+        let mut acyclic = MinMeanCycle::<i32>::new();
+        acyclic.add_edge(0, 1, 1);
+        assert_eq!(acyclic.karp(), None);
+        assert_eq!(acyclic.howard(), None);
+    }
+}
@@ -0,0 +1,443 @@
+use std::{collections::HashMap, hash::Hash};
+
+use crate::flows::{
+    dfs_lca_network_simplex, dinic::Dinic, network_simplex, parametric_max_flow, Cost, Flow,
+};
+
+/// Flow engine that [`Network::build_max_flow`] can lower a network into.
+pub trait MaxFlowEngine<F> {
+    type EdgeId;
+    fn add_edge(&mut self, src: usize, dst: usize, capacity: F) -> Self::EdgeId;
+}
+
+/// Flow engine that [`Network::build_min_cost_flow`] can lower a network into.
+pub trait MinCostFlowEngine<F, C> {
+    type EdgeId;
+    fn add_edge(&mut self, src: usize, dst: usize, lower: F, upper: F, cost: C) -> Self::EdgeId;
+    fn add_supply(&mut self, v: usize, b: F);
+}
+
+/// Anything that can tell the flow on an edge of an engine, e.g. the engine itself after a max
+/// flow or the result of a min cost flow.
+pub trait FlowResult<F> {
+    type EdgeId;
+    fn get_flow(&self, e: &Self::EdgeId) -> F;
+}
+
+/// Anything that can tell the potential of a vertex of an engine.
+pub trait PotentialResult<C> {
+    fn get_potential(&self, v: usize) -> C;
+}
+
+impl<F: Flow> MaxFlowEngine<F> for Dinic<F> {
+    type EdgeId = crate::flows::dinic::EdgeId;
+    fn add_edge(&mut self, src: usize, dst: usize, capacity: F) -> Self::EdgeId {
+        Dinic::add_edge(self, src, dst, capacity)
+    }
+}
+impl<F: Flow> FlowResult<F> for Dinic<F> {
+    type EdgeId = crate::flows::dinic::EdgeId;
+    fn get_flow(&self, e: &Self::EdgeId) -> F {
+        Dinic::get_flow(self, e)
+    }
+}
+
+impl<F: Flow + num::Integer> MaxFlowEngine<F> for parametric_max_flow::ParametricMaxFlow<F> {
+    type EdgeId = ();
+    fn add_edge(&mut self, src: usize, dst: usize, capacity: F) {
+        parametric_max_flow::ParametricMaxFlow::add_edge(self, src, dst, capacity)
+    }
+}
+
+macro_rules! implement_min_cost_flow {
+    ($m:ident) => {
+        impl<F: Flow, C: Cost> MinCostFlowEngine<F, C> for $m::NetworkSimplex<F, C> {
+            type EdgeId = $m::EdgeId;
+            fn add_edge(
+                &mut self,
+                src: usize,
+                dst: usize,
+                lower: F,
+                upper: F,
+                cost: C,
+            ) -> Self::EdgeId {
+                $m::NetworkSimplex::add_edge(self, src, dst, lower, upper, cost)
+            }
+            fn add_supply(&mut self, v: usize, b: F) {
+                $m::NetworkSimplex::add_supply(self, v, b)
+            }
+        }
+        impl<F: Flow, C: Cost> FlowResult<F> for $m::Ret<F, C> {
+            type EdgeId = $m::EdgeId;
+            fn get_flow(&self, e: &Self::EdgeId) -> F {
+                $m::Ret::get_flow(self, e)
+            }
+        }
+        impl<F: Flow, C: Cost> PotentialResult<C> for $m::Ret<F, C> {
+            fn get_potential(&self, v: usize) -> C {
+                $m::Ret::get_potential(self, v)
+            }
+        }
+    };
+}
+implement_min_cost_flow!(network_simplex);
+implement_min_cost_flow!(dfs_lca_network_simplex);
+
+struct Arc<F, C> {
+    src: usize,
+    dst: usize,
+    lower: F,
+    upper: F,
+    cost: C,
+}
+
+struct Vertex<L, F, C> {
+    label: L,
+    capacity: Option<Arc<F, C>>,
+    supply: F,
+}
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct EdgeId(usize);
+
+/// Modelling layer over the flow engines.
+///
+/// Vertices are allocated by labels, and a vertex with a capacity is split into an in-node and
+/// an out-node joined by an arc carrying that capacity and cost. The capacity bounds all the
+/// flow through the vertex, including the supply starting there and the demand ending there,
+/// and hence sources and sinks should be taken as [`Mapping::source`] and [`Mapping::sink`].
+///
+/// An undirected edge becomes a pair of opposite arcs, and its flow is reported as the net flow
+/// from the first endpoint to the second. With a cost, the cost should be non-negative so that
+/// both arcs are never used at the same time.
+pub struct Network<L, F: Flow, C: Cost = F> {
+    ids: HashMap<L, usize>,
+    vertices: Vec<Vertex<L, F, C>>,
+    arcs: Vec<Arc<F, C>>,
+    // forward arc and backward arc if undirected
+    edges: Vec<(usize, Option<usize>)>,
+}
+
+impl<L: Hash + Eq + Clone, F: Flow, C: Cost> Default for Network<L, F, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Hash + Eq + Clone, F: Flow, C: Cost> Network<L, F, C> {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            vertices: Vec::new(),
+            arcs: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the vertex with the label, allocating it if it doesn't exist.
+    /// Indices are contiguous from `0` in the order of allocation.
+    pub fn vertex(&mut self, label: L) -> usize {
+        let vertices = &mut self.vertices;
+        *self.ids.entry(label).or_insert_with_key(|label| {
+            vertices.push(Vertex {
+                label: label.clone(),
+                capacity: None,
+                supply: F::zero(),
+            });
+            vertices.len() - 1
+        })
+    }
+
+    pub fn get_vertex(&self, label: &L) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn get_label(&self, v: usize) -> &L {
+        &self.vertices[v].label
+    }
+
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    fn add_arc(&mut self, src: L, dst: L, lower: F, upper: F, cost: C) -> usize {
+        assert!(
+            lower <= upper,
+            "lower {} should be less or equal to upper {}",
+            lower,
+            upper
+        );
+        let src = self.vertex(src);
+        let dst = self.vertex(dst);
+        self.arcs.push(Arc {
+            src,
+            dst,
+            lower,
+            upper,
+            cost,
+        });
+        self.arcs.len() - 1
+    }
+
+    pub fn add_edge(&mut self, src: L, dst: L, capacity: F) -> EdgeId {
+        self.add_edge_with_cost(src, dst, F::zero(), capacity, C::zero())
+    }
+
+    pub fn add_edge_with_cost(&mut self, src: L, dst: L, lower: F, upper: F, cost: C) -> EdgeId {
+        let a = self.add_arc(src, dst, lower, upper, cost);
+        self.edges.push((a, None));
+        EdgeId(self.edges.len() - 1)
+    }
+
+    pub fn add_undirected_edge(&mut self, u: L, v: L, capacity: F) -> EdgeId {
+        self.add_undirected_edge_with_cost(u, v, capacity, C::zero())
+    }
+
+    pub fn add_undirected_edge_with_cost(&mut self, u: L, v: L, capacity: F, cost: C) -> EdgeId {
+        assert!(
+            !cost.is_negative(),
+            "cost {} of an undirected edge should be non-negative",
+            cost
+        );
+        let a = self.add_arc(u.clone(), v.clone(), F::zero(), capacity, cost);
+        let b = self.add_arc(v, u, F::zero(), capacity, cost);
+        self.edges.push((a, Some(b)));
+        EdgeId(self.edges.len() - 1)
+    }
+
+    pub fn set_vertex_capacity(&mut self, v: L, capacity: F) {
+        self.set_vertex_capacity_with_cost(v, F::zero(), capacity, C::zero())
+    }
+
+    /// Bounds the flow through `v` by `[lower, upper]` and charges `cost` per unit of it.
+    pub fn set_vertex_capacity_with_cost(&mut self, v: L, lower: F, upper: F, cost: C) {
+        assert!(
+            lower <= upper,
+            "lower {} should be less or equal to upper {}",
+            lower,
+            upper
+        );
+        let v = self.vertex(v);
+        self.vertices[v].capacity = Some(Arc {
+            src: v,
+            dst: v,
+            lower,
+            upper,
+            cost,
+        });
+    }
+
+    pub fn add_supply(&mut self, v: L, b: F) {
+        let v = self.vertex(v);
+        self.vertices[v].supply += b;
+    }
+
+    pub fn add_demand(&mut self, v: L, b: F) {
+        self.add_supply(v, -b);
+    }
+
+    fn assign_nodes<E>(&self) -> Mapping<'_, L, F, C, E> {
+        let n = self.vertices.len();
+        let mut out_node: Vec<_> = (0..n).collect();
+        let mut next = n;
+        for (v, vertex) in self.vertices.iter().enumerate() {
+            if vertex.capacity.is_some() {
+                out_node[v] = next;
+                next += 1;
+            }
+        }
+        Mapping {
+            network: self,
+            out_node,
+            vertex_arcs: Vec::with_capacity(n),
+            arcs: Vec::with_capacity(self.arcs.len()),
+        }
+    }
+
+    /// Lowers the network into a max flow engine, ignoring costs.
+    /// Panics if there are lower bounds or supplies, which max flow can't express.
+    pub fn build_max_flow<E: MaxFlowEngine<F>>(
+        &self,
+        engine: &mut E,
+    ) -> Mapping<'_, L, F, C, E::EdgeId> {
+        let mut mapping = self.assign_nodes();
+        let arc_ok = |a: &Arc<F, C>| a.lower.is_zero();
+        for (v, vertex) in self.vertices.iter().enumerate() {
+            assert!(
+                vertex.supply.is_zero(),
+                "Max flow can't have supplies, but some vertex has supply {}",
+                vertex.supply
+            );
+            mapping.vertex_arcs.push(vertex.capacity.as_ref().map(|a| {
+                assert!(arc_ok(a), "Max flow can't have lower bounds");
+                engine.add_edge(v, mapping.out_node[v], a.upper)
+            }));
+        }
+        for a in &self.arcs {
+            assert!(arc_ok(a), "Max flow can't have lower bounds");
+            let e = engine.add_edge(mapping.out_node[a.src], a.dst, a.upper);
+            mapping.arcs.push(e);
+        }
+        mapping
+    }
+
+    /// Lowers the network into a min cost flow engine.
+    pub fn build_min_cost_flow<E: MinCostFlowEngine<F, C>>(
+        &self,
+        engine: &mut E,
+    ) -> Mapping<'_, L, F, C, E::EdgeId> {
+        let mut mapping = self.assign_nodes();
+        for (v, vertex) in self.vertices.iter().enumerate() {
+            mapping.vertex_arcs.push(
+                vertex
+                    .capacity
+                    .as_ref()
+                    .map(|a| engine.add_edge(v, mapping.out_node[v], a.lower, a.upper, a.cost)),
+            );
+            if vertex.supply.is_positive() {
+                engine.add_supply(mapping.source(&vertex.label), vertex.supply);
+            } else if vertex.supply.is_negative() {
+                engine.add_supply(mapping.sink(&vertex.label), vertex.supply);
+            }
+        }
+        for a in &self.arcs {
+            let e = engine.add_edge(mapping.out_node[a.src], a.dst, a.lower, a.upper, a.cost);
+            mapping.arcs.push(e);
+        }
+        mapping
+    }
+}
+
+/// Correspondence between a [`Network`] and the vertices and edges of an engine it was lowered
+/// into.
+pub struct Mapping<'a, L, F: Flow, C: Cost, E> {
+    network: &'a Network<L, F, C>,
+    out_node: Vec<usize>,
+    vertex_arcs: Vec<Option<E>>,
+    arcs: Vec<E>,
+}
+
+impl<'a, L: Hash + Eq + Clone, F: Flow, C: Cost, E> Mapping<'a, L, F, C, E> {
+    fn id(&self, label: &L) -> usize {
+        self.network
+            .get_vertex(label)
+            .expect("No vertex with the label")
+    }
+
+    /// Engine vertex where the flow enters the vertex with the label.
+    pub fn source(&self, label: &L) -> usize {
+        self.id(label)
+    }
+
+    /// Engine vertex where the flow leaves the vertex with the label.
+    pub fn sink(&self, label: &L) -> usize {
+        self.out_node[self.id(label)]
+    }
+
+    /// Number of engine vertices used.
+    pub fn num_nodes(&self) -> usize {
+        self.out_node
+            .iter()
+            .fold(self.out_node.len(), |n, &v| n.max(v + 1))
+    }
+
+    pub fn get_flow<R: FlowResult<F, EdgeId = E>>(&self, result: &R, e: &EdgeId) -> F {
+        let (a, b) = self.network.edges[e.0];
+        let f = result.get_flow(&self.arcs[a]);
+        match b {
+            Some(b) => f - result.get_flow(&self.arcs[b]),
+            None => f,
+        }
+    }
+
+    /// Flow through a vertex with a capacity.
+    pub fn get_vertex_flow<R: FlowResult<F, EdgeId = E>>(&self, result: &R, label: &L) -> F {
+        let a = self.vertex_arcs[self.id(label)]
+            .as_ref()
+            .expect("Vertex without capacity");
+        result.get_flow(a)
+    }
+
+    pub fn get_potential<R: PotentialResult<C>>(&self, result: &R, label: &L) -> C {
+        result.get_potential(self.source(label))
+    }
+
+    /// Labels of vertices whose in-node is in `cut`, e.g. the source side returned by
+    /// `Dinic::max_flow`.
+    pub fn get_source_side(&self, cut: &[usize]) -> Vec<&'a L> {
+        let n = self.out_node.len();
+        cut.iter()
+            .filter(|&&v| v < n)
+            .map(|&v| self.network.get_label(v))
+            .collect()
+    }
+
+    /// Labels of vertices with a capacity separated by `cut`, i.e. whose in-node is in `cut` but
+    /// out-node is not.
+    pub fn get_cut_vertices(&self, cut: &[usize]) -> Vec<&'a L> {
+        let nodes = self.num_nodes();
+        let mut in_cut = vec![false; nodes];
+        cut.iter()
+            .filter(|&&v| v < nodes)
+            .for_each(|&v| in_cut[v] = true);
+        (0..self.out_node.len())
+            .filter(|&v| self.out_node[v] != v && in_cut[v] && !in_cut[self.out_node[v]])
+            .map(|v| self.network.get_label(v))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        // grid 2 x 3 with unit vertex capacities, undirected edges
+        let mut net: Network<(usize, usize), i32> = Network::new();
+        let mut edges = vec![];
+        for i in 0..2 {
+            for j in 0..3 {
+                net.set_vertex_capacity((i, j), 1);
+                if i + 1 < 2 {
+                    edges.push(net.add_undirected_edge((i, j), (i + 1, j), 10));
+                }
+                if j + 1 < 3 {
+                    edges.push(net.add_undirected_edge((i, j), (i, j + 1), 10));
+                }
+            }
+        }
+        net.add_edge((9, 9), (0, 0), 10);
+        net.add_edge((9, 9), (1, 0), 10);
+        net.add_edge((1, 2), (8, 8), 10);
+        net.add_edge((0, 2), (8, 8), 10);
+        let mut dinic = Dinic::new();
+        let mapping = net.build_max_flow(&mut dinic);
+        let (flow, cut) = dinic.max_flow(mapping.source(&(9, 9)), mapping.sink(&(8, 8)));
+        assert_eq!(flow, 2);
+        assert_eq!(mapping.get_cut_vertices(&cut), vec![&(0, 0), &(1, 0)]);
+        assert_eq!(mapping.get_vertex_flow(&dinic, &(0, 1)), 1);
+        assert_eq!(mapping.get_flow(&dinic, &edges[1]), 1);
+
+        // send two units from "c" to "a", paying 100 per unit passing through "c"
+        let mut net: Network<&str, i32> = Network::new();
+        net.add_undirected_edge_with_cost("a", "b", 1, 1);
+        let bc = net.add_undirected_edge_with_cost("b", "c", 1, 1);
+        net.add_undirected_edge_with_cost("a", "d", 1, 5);
+        let cd = net.add_undirected_edge_with_cost("c", "d", 1, 1);
+        net.add_supply("c", 2);
+        net.add_demand("a", 2);
+        net.set_vertex_capacity_with_cost("c", 0, 2, 100);
+        let mut ns = network_simplex::NetworkSimplex::new();
+        let mapping = net.build_min_cost_flow(&mut ns);
+        let ret = ns.run().unwrap();
+        assert_eq!(ret.get_value::<i32>(), 208);
+        assert_eq!(mapping.get_flow(&ret, &bc), -1);
+        assert_eq!(mapping.get_flow(&ret, &cd), 1);
+        assert_eq!(mapping.get_vertex_flow(&ret, &"c"), 2);
+    }
+}
@@ -4,10 +4,12 @@ use std::{
     collections::HashSet,
     ops::{Add, Mul},
     option::Option::{None, Some},
+    time::{Duration, Instant},
 };
 
 use crate::flows::{Cost, Flow, Zero};
 
+#[derive(Clone)]
 struct Edge<F, C> {
     src: usize,
     dst: usize,
@@ -56,9 +58,53 @@ struct TemporaryData<C: Cost> {
     next_scan_start: usize,
 }
 
+/// Limits on [`NetworkSimplex::run_with_config`]. Both are unlimited by default.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    pub max_pivots: Option<usize>,
+    pub time_limit: Option<Duration>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    pub pivots: usize,
+    /// Pivots that change no flow.
+    pub degenerate_pivots: usize,
+    pub selection_time: Duration,
+    pub update_time: Duration,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Error {
+    Infeasible,
+    PivotLimit,
+    TimeLimit,
+}
+
+/// Passed to the callback of [`NetworkSimplex::run_with_config`] after each pivot.
+pub struct Pivot<F, T> {
+    /// `None` if an artificial edge entered the tree.
+    pub entering: Option<EdgeId>,
+    /// Flow pushed along the cycle, negative if `entering` was pushed backward.
+    pub flow: F,
+    /// Cost of the flow on the real edges. Flow may still go through the artificial edges, so it
+    /// is not feasible until then.
+    pub objective: T,
+}
+
+/// Spanning tree of the final basis, rooted at the artificial root vertex.
+struct Tree {
+    // parent and the edge from the vertex to the parent, which is artificial if out of range
+    parent: Vec<Option<(usize, EdgeId)>>,
+    depth: Vec<usize>,
+    // pre-order interval of the subtree
+    order: Vec<(usize, usize)>,
+}
+
 pub struct Ret<F, C> {
-    edges: Vec<(F, C)>,
+    edges: Vec<Edge<F, C>>,
     potential: Vec<C>,
+    tree: Tree,
 }
 impl<F: Flow, C: Cost> Ret<F, C> {
     pub fn get_value<T>(&self) -> T
@@ -67,16 +113,133 @@ impl<F: Flow, C: Cost> Ret<F, C> {
     {
         self.edges
             .iter()
-            .filter(|(f, _)| f.is_positive())
-            .map(|(f, c)| T::from(*f) * T::from(*c))
+            .filter(|e| e.flow.is_positive())
+            .map(|e| T::from(e.flow) * T::from(e.cost))
             .fold(T::zero(), |a, b| a + b)
     }
     pub fn get_flow(&self, e: &EdgeId) -> F {
-        self.edges[e.0].0
+        self.edges[e.0].flow
     }
     pub fn get_potential(&self, v: usize) -> C {
         self.potential[v]
     }
+
+    /// `cost + p[src] - p[dst]`, which is non-negative unless the flow is at the upper bound,
+    /// and non-positive unless it is at the lower bound.
+    pub fn get_reduced_cost(&self, e: &EdgeId) -> C {
+        let e = &self.edges[e.0];
+        e.cost + self.potential[e.src] - self.potential[e.dst]
+    }
+
+    /// Whether the edge is in the spanning tree of the final basis.
+    pub fn is_basic(&self, e: &EdgeId) -> bool {
+        self.child_side(e).is_some()
+    }
+
+    /// The endpoint of a basic edge farther from the root, and whether it is `dst`.
+    fn child_side(&self, e: &EdgeId) -> Option<(usize, bool)> {
+        let Edge { src, dst, .. } = self.edges[e.0];
+        if self.tree.parent[src] == Some((dst, *e)) {
+            Some((src, false))
+        } else if self.tree.parent[dst] == Some((src, e.rev())) {
+            Some((dst, true))
+        } else {
+            None
+        }
+    }
+
+    fn in_subtree(&self, v: usize, root: usize) -> bool {
+        let (l, r) = self.tree.order[root];
+        l <= self.tree.order[v].0 && self.tree.order[v].0 < r
+    }
+
+    /// Range of the cost of the edge over which the current flow stays optimal, where `None`
+    /// means unbounded. Takes `O(m)` time for a basic edge.
+    pub fn get_cost_range(&self, e: &EdgeId) -> (Option<C>, Option<C>) {
+        let cost = self.edges[e.0].cost;
+        let (child, down) = match self.child_side(e) {
+            Some(side) => side,
+            None => {
+                // only the reduced cost of this edge changes
+                let rc = self.get_reduced_cost(e);
+                let at_lower = self.edges[e.0 ^ 1].flow == self.edges[e.0 ^ 1].capacity;
+                let at_upper = self.edges[e.0].flow == self.edges[e.0].capacity;
+                return match (at_lower, at_upper) {
+                    (true, false) => (Some(cost - rc), None),
+                    (false, true) => (None, Some(cost - rc)),
+                    (true, true) => (None, None),
+                    (false, false) => (Some(cost), Some(cost)),
+                };
+            }
+        };
+        // Changing the cost by `d` shifts the potentials in the subtree by `d` if the edge points
+        // down, and by `-d` otherwise, which changes the reduced costs of the edges across.
+        let (mut lower, mut upper): (Option<C>, Option<C>) = (None, None);
+        for (i, g) in self.edges.iter().enumerate().step_by(2) {
+            let g_id = EdgeId(i);
+            if self.child_side(&g_id).is_some() {
+                continue;
+            }
+            let sign = match (self.in_subtree(g.src, child), self.in_subtree(g.dst, child)) {
+                (true, false) => down,
+                (false, true) => !down,
+                _ => continue,
+            };
+            let rc = self.get_reduced_cost(&g_id);
+            let at_lower = self.edges[i ^ 1].flow == self.edges[i ^ 1].capacity;
+            let at_upper = g.flow == g.capacity;
+            // `rc + d` (if `sign`) or `rc - d` should stay non-negative at the lower bound and
+            // non-positive at the upper bound
+            if at_lower && !at_upper {
+                if sign {
+                    lower = Some(lower.map_or(-rc, |l| max(l, -rc)));
+                } else {
+                    upper = Some(upper.map_or(rc, |u| min(u, rc)));
+                }
+            }
+            if at_upper && !at_lower {
+                if sign {
+                    upper = Some(upper.map_or(-rc, |u| min(u, -rc)));
+                } else {
+                    lower = Some(lower.map_or(rc, |l| max(l, rc)));
+                }
+            }
+        }
+        (lower.map(|d| cost + d), upper.map(|d| cost + d))
+    }
+
+    /// Range of `d` such that adding `d` to the supply of `src` and the demand of `dst` keeps the
+    /// current basis feasible, and hence the potentials optimal. Within the range the flow
+    /// changes only along the tree path, and the value changes by `d * (p[dst] - p[src])`.
+    ///
+    /// Returns `None` if the tree path goes through an artificial edge, i.e. `src` and `dst` are
+    /// not connected by the real edges of the basis.
+    pub fn get_supply_range(&self, src: usize, dst: usize) -> Option<(F, F)> {
+        assert_ne!(src, dst, "Source and sink vertex should be different");
+        let residual = |e: EdgeId| -> Option<F> {
+            let e = self.edges.get(e.0)?;
+            Some(e.capacity - e.flow)
+        };
+        let (mut lower, mut upper) = (None, None);
+        let mut a = src;
+        let mut b = dst;
+        while a != b {
+            // `e` is on the path in the direction from `src` to `dst`
+            let e = if self.tree.depth[a] >= self.tree.depth[b] {
+                let (p, e) = self.tree.parent[a].unwrap();
+                a = p;
+                e
+            } else {
+                let (p, e) = self.tree.parent[b].unwrap();
+                b = p;
+                e.rev()
+            };
+            let (forward, backward) = (residual(e)?, residual(e.rev())?);
+            upper = Some(upper.map_or(forward, |u| min(u, forward)));
+            lower = Some(lower.map_or(-backward, |l| max(l, -backward)));
+        }
+        Some((lower?, upper?))
+    }
 }
 
 impl<F: Flow, C: Cost> NetworkSimplex<F, C> {
@@ -255,7 +418,11 @@ impl<F: Flow, C: Cost> NetworkSimplex<F, C> {
         None
     }
 
-    fn pivot(&mut self, data: &mut TemporaryData<C>, eid: EdgeId) {
+    /// Returns the amount of flow pushed along the cycle and the cost of the cycle over the real
+    /// edges, i.e. without the penalty on the artificial ones.
+    fn pivot(&mut self, data: &mut TemporaryData<C>, eid: EdgeId) -> (F, C) {
+        let num_real_edges = self.edges.len() - 2 * (data.n - 1);
+        let mut cost = C::zero();
         let entering_edge = self.get_edge(&eid);
         let Edge { src, dst, .. } = *entering_edge;
         let mut f = Self::residual_capacity(entering_edge);
@@ -286,6 +453,9 @@ impl<F: Flow, C: Cost> NetworkSimplex<F, C> {
         while a != top {
             let v_data = &data.vertices[a];
             let down_edge = v_data.parent_edge.unwrap().rev();
+            if down_edge.0 < num_real_edges {
+                cost += self.get_edge(&down_edge).cost;
+            }
             if self.add_flow(&down_edge, f) {
                 if leaving_edge_id.is_none() {
                     leaving_edge_id = Some(down_edge);
@@ -294,6 +464,9 @@ impl<F: Flow, C: Cost> NetworkSimplex<F, C> {
             }
             a = v_data.parent.unwrap();
         }
+        if eid.0 < num_real_edges {
+            cost += self.get_edge(&eid).cost;
+        }
         if self.add_flow(&eid, f) {
             leaving_edge_id = Some(eid);
             leaving_side = LeavingSide::ENTER;
@@ -302,6 +475,9 @@ impl<F: Flow, C: Cost> NetworkSimplex<F, C> {
         while b != top {
             let v_data = &data.vertices[b];
             let up_edge = v_data.parent_edge.unwrap();
+            if up_edge.0 < num_real_edges {
+                cost += self.get_edge(&up_edge).cost;
+            }
             if self.add_flow(&up_edge, f) {
                 leaving_edge_id = Some(up_edge);
                 leaving_side = LeavingSide::DST;
@@ -311,7 +487,7 @@ impl<F: Flow, C: Cost> NetworkSimplex<F, C> {
         let leaving_edge_id = leaving_edge_id.unwrap();
         let leaving_e = self.get_edge(&leaving_edge_id);
         if leaving_edge_id == eid {
-            return;
+            return (f, cost);
         }
         assert!(data.vertices[src].tree_edges.insert(eid));
         assert!(data.vertices[dst].tree_edges.insert(eid.rev()));
@@ -324,28 +500,166 @@ impl<F: Flow, C: Cost> NetworkSimplex<F, C> {
         match leaving_side {
             LeavingSide::SRC => self.update_tree(data, dst),
             LeavingSide::DST => self.update_tree(data, src),
-            LeavingSide::ENTER => return,
+            LeavingSide::ENTER => {}
         }
+        (f, cost)
     }
 
     pub fn run(&mut self) -> Option<Ret<F, C>> {
+        self.run_impl(&Config::default(), None, |_, _, _| {}).ok()
+    }
+
+    /// Runs with limits, returning the statistics even if it stops halfway.
+    /// `callback` is called after each pivot.
+    ///
+    /// On [`Error::PivotLimit`] and [`Error::TimeLimit`], the flows and the supplies are restored
+    /// to those before the run, so it may be run again, e.g. with larger limits.
+    pub fn run_with_config<T, CB>(
+        &mut self,
+        config: &Config,
+        mut callback: CB,
+    ) -> (Result<Ret<F, C>, Error>, Stats)
+    where
+        T: From<F> + From<C> + Mul<Output = T> + Add<Output = T> + Zero + Clone,
+        CB: FnMut(&Pivot<F, T>),
+    {
+        let num_edges = self.edges.len();
+        let mut objective = None;
+        let mut stats = Stats::default();
+        let ret = self.run_impl(config, Some(&mut stats), |edges, eid, f| {
+            let value = match objective.take() {
+                Some(value) => value + T::from(f) * T::from(eid.1),
+                None => edges[..num_edges]
+                    .iter()
+                    .filter(|e| e.flow.is_positive())
+                    .map(|e| T::from(e.flow) * T::from(e.cost))
+                    .fold(T::zero(), |a, b| a + b),
+            };
+            let (entering, flow) = if eid.0 .0 >= num_edges {
+                (None, f)
+            } else if eid.0 .0 & 1 == 0 {
+                (Some(eid.0), f)
+            } else {
+                (Some(eid.0.rev()), -f)
+            };
+            callback(&Pivot {
+                entering,
+                flow,
+                objective: value.clone(),
+            });
+            objective = Some(value);
+        });
+        (ret, stats)
+    }
+
+    /// `on_pivot` takes the edges after the pivot, the entering edge with the cost of the cycle
+    /// over the real edges, and the amount of flow pushed. Without `stats`, the clock is never read and the limits of
+    /// `config` are ignored.
+    fn run_impl<CB>(
+        &mut self,
+        config: &Config,
+        mut stats: Option<&mut Stats>,
+        mut on_pivot: CB,
+    ) -> Result<Ret<F, C>, Error>
+    where
+        CB: FnMut(&[Edge<F, C>], (EdgeId, C), F),
+    {
+        let now = |stats: &Option<&mut Stats>| stats.as_ref().map(|_| Instant::now());
+        let start = now(&stats);
         let mut data = self.prepare_data();
-        while let Some(eid) = self.select_edge(&mut data) {
-            self.pivot(&mut data, eid);
+        let mut error = None;
+        loop {
+            let t0 = now(&stats);
+            if let (Some(time_limit), Some(start), Some(t0)) = (config.time_limit, start, t0) {
+                if t0 - start >= time_limit {
+                    error = Some(Error::TimeLimit);
+                    break;
+                }
+            }
+            let eid = match self.select_edge(&mut data) {
+                Some(eid) => eid,
+                None => break,
+            };
+            let t1 = now(&stats);
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.selection_time += t1.unwrap() - t0.unwrap();
+                if let Some(max_pivots) = config.max_pivots {
+                    if stats.pivots >= max_pivots {
+                        error = Some(Error::PivotLimit);
+                        break;
+                    }
+                }
+            }
+            let (f, cost) = self.pivot(&mut data, eid);
+            if let Some(stats) = stats.as_deref_mut() {
+                stats.update_time += t1.unwrap().elapsed();
+                stats.pivots += 1;
+                if f.is_zero() {
+                    stats.degenerate_pivots += 1;
+                }
+            }
+            on_pivot(&self.edges, (eid, cost), f);
+        }
+        let artificial = self.edges.split_off(self.edges.len() - 2 * (data.n - 1));
+        if let Some(error) = error {
+            // back to the lower bounds, and the supplies are the capacities of the artificial
+            // edges
+            for pair in self.edges.chunks_exact_mut(2) {
+                let lower = -pair[1].capacity;
+                pair[0].flow = lower;
+                pair[1].flow = -lower;
+            }
+            for e in artificial.iter().step_by(2) {
+                if e.dst == data.root {
+                    self.balances[e.src] = e.capacity - F::one();
+                } else {
+                    self.balances[e.dst] = -e.capacity;
+                }
+            }
+            return Err(error);
         }
-        for e in self.edges.split_off(self.edges.len() - 2 * (data.n - 1)) {
+        for e in &artificial {
             if !e.flow.is_zero() {
-                return None;
+                return Err(Error::Infeasible);
             }
         }
-        Some(Ret {
-            edges: self.edges.iter().map(|e| (e.flow, e.cost)).collect(),
+        let mut tree = Tree {
+            parent: vec![None; data.n],
+            depth: vec![0; data.n],
+            order: vec![(0, 0); data.n],
+        };
+        let mut time = 0;
+        let mut stack = vec![(data.root, true)];
+        while let Some((v, enter)) = stack.pop() {
+            if !enter {
+                tree.order[v].1 = time;
+                continue;
+            }
+            let v_data = &data.vertices[v];
+            tree.order[v].0 = time;
+            time += 1;
+            tree.depth[v] = v_data.depth;
+            tree.parent[v] = v_data.parent.zip(v_data.parent_edge);
+            stack.push((v, false));
+            for eid in v_data.tree_edges.iter() {
+                let e = match self.edges.get(eid.0) {
+                    Some(e) => e,
+                    None => &artificial[eid.0 - self.edges.len()],
+                };
+                if Some(e.dst) != v_data.parent {
+                    stack.push((e.dst, true));
+                }
+            }
+        }
+        Ok(Ret {
+            edges: self.edges.clone(),
             potential: data
                 .vertices
                 .iter()
                 .take(data.n - 1)
                 .map(|v| v.potential)
                 .collect(),
+            tree,
         })
     }
 }
@@ -375,5 +689,69 @@ mod test {
         let offset = potential[0];
         potential.iter_mut().for_each(|p| *p -= offset);
         assert_eq!(potential, vec![0, -1, -1]);
+
+        let build = || {
+            let mut ns: NetworkSimplex<i32, i32> = NetworkSimplex::new();
+            ns.add_supply(0, 3);
+            ns.add_demand(2, 3);
+            ns.add_edge(0, 1, 0, 2, 1);
+            ns.add_edge(1, 2, 0, 2, 1);
+            ns.add_edge(0, 2, 0, 2, 3);
+            ns
+        };
+        let mut pivots = Vec::new();
+        let (ret, stats) = build().run_with_config(&Config::default(), |p: &Pivot<i32, i64>| {
+            pivots.push(p.objective)
+        });
+        assert_eq!(ret.unwrap().get_value::<i64>(), 7);
+        assert_eq!(pivots.len(), stats.pivots);
+        // only the real edges are counted
+        assert_eq!(pivots.last(), Some(&7));
+        assert!(pivots.iter().all(|&p| (0..=7).contains(&p)));
+
+        // stopped runs leave the instance as it was
+        let config = Config {
+            max_pivots: Some(1),
+            ..Config::default()
+        };
+        let mut ns = build();
+        let (ret, stats) = ns.run_with_config(&config, |_: &Pivot<i32, i64>| {});
+        assert_eq!(ret.err(), Some(Error::PivotLimit));
+        assert_eq!(stats.pivots, 1);
+        assert_eq!(ns.run().unwrap().get_value::<i64>(), 7);
+
+        let config = Config {
+            time_limit: Some(Duration::ZERO),
+            ..Config::default()
+        };
+        let mut ns = build();
+        let (ret, stats) = ns.run_with_config(&config, |_: &Pivot<i32, i64>| {});
+        assert_eq!(ret.err(), Some(Error::TimeLimit));
+        assert_eq!(stats.pivots, 0);
+        assert_eq!(ns.run().unwrap().get_value::<i64>(), 7);
+
+        // the path through 1 stays the best while it is not more expensive than the direct edge
+        let mut ns: NetworkSimplex<i32, i32> = NetworkSimplex::new();
+        ns.add_supply(0, 2);
+        ns.add_demand(2, 2);
+        let e01 = ns.add_edge(0, 1, 0, 3, 1);
+        ns.add_edge(1, 2, 0, 3, 1);
+        let e02 = ns.add_edge(0, 2, 0, 2, 3);
+        let ret = ns.run().unwrap();
+        assert_eq!(ret.get_reduced_cost(&e02), 1);
+        assert!(ret.is_basic(&e01) && !ret.is_basic(&e02));
+        assert_eq!(ret.get_cost_range(&e02), (Some(2), None));
+        assert_eq!(ret.get_cost_range(&e01), (None, Some(2)));
+        assert_eq!(ret.get_supply_range(0, 2), Some((-2, 1)));
+
+        // no real tree edges between the components
+        let mut ns: NetworkSimplex<i32, i32> = NetworkSimplex::new();
+        ns.add_supply(0, 2);
+        ns.add_demand(1, 2);
+        ns.add_edge(0, 1, 0, 3, 1);
+        ns.add_edge(2, 3, 0, 3, 1);
+        let ret = ns.run().unwrap();
+        assert_eq!(ret.get_supply_range(0, 1), Some((-2, 1)));
+        assert_eq!(ret.get_supply_range(0, 3), None);
     }
 }
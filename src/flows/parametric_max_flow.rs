@@ -0,0 +1,839 @@
+use std::{
+    cmp::{max, min},
+    collections::VecDeque,
+    mem,
+};
+
+use num::{rational::Ratio, CheckedAdd, CheckedMul, Integer};
+
+use crate::flows::{Flow, One, Zero};
+
+struct Edge<F> {
+    src: usize,
+    dst: usize,
+    constant: F,
+    slope: F,
+}
+
+impl<F: Flow + Integer> Edge<F> {
+    /// capacity at lambda = p / q, multiplied by q
+    fn scaled_capacity(&self, p: F, q: F) -> F {
+        self.constant * q + self.slope * p
+    }
+
+    /// capacity at lambda = p / q, multiplied by `scale`, a multiple of q
+    fn capacity(&self, p: F, q: F, scale: F) -> F {
+        self.constant * scale + self.slope * p * (scale / q)
+    }
+
+    /// The tail and the head in the graph itself, or in the reverse graph if `reverse`.
+    fn ends(&self, reverse: bool) -> (usize, usize) {
+        if reverse {
+            (self.dst, self.src)
+        } else {
+            (self.src, self.dst)
+        }
+    }
+}
+
+/// A min cut of capacity `line.0 + line.1 * lambda` waiting on the stack, where `diff` holds the
+/// vertices of its source side that are not in the next smaller cut, together with the run kept
+/// for them.
+struct Cut<F> {
+    line: (F, F),
+    diff: Vec<usize>,
+    run: Option<Run<F>>,
+}
+
+/// Push-relabel on the vertices of a subproblem, with the decided vertices contracted to the
+/// source or the sink. It runs on the graph itself, where lambda only increases, or on the
+/// reverse graph with the source and the sink swapped, where lambda only decreases, so that the
+/// preflow and the labels stay valid as lambda moves.
+struct Run<F> {
+    reverse: bool,
+    vertices: Vec<usize>,
+    // lambda = p / q, and the flows are multiplied by `scale`, a multiple of q
+    p: F,
+    q: F,
+    scale: F,
+    queue: VecDeque<usize>,
+}
+
+impl<F: Flow + Integer> Run<F> {
+    /// Labels at least this are of the vertices that cannot reach the sink.
+    fn n(&self) -> usize {
+        self.vertices.len() + 2
+    }
+
+    fn capacity(&self, e: &Edge<F>) -> F {
+        e.capacity(self.p, self.q, self.scale)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Side {
+    Source,
+    Inner,
+    Sink,
+}
+
+/// Preflows of the runs on the graph itself or on the reverse graph. The runs of a kind are on
+/// disjoint vertices, and each edge belongs to the run on its tail, if any.
+struct Preflow<F> {
+    flow: Vec<F>,
+    excess: Vec<F>,
+    label: Vec<usize>,
+    current: Vec<usize>,
+    queued: Vec<bool>,
+}
+
+struct TemporaryData<F> {
+    out_edges: Vec<Vec<usize>>,
+    in_edges: Vec<Vec<usize>>,
+    // the edges other than self-loops
+    incident: Vec<Vec<usize>>,
+    in_source_side: Vec<bool>,
+    // index of the pending cut whose `diff` has the vertex, and that of the top
+    owner: Vec<usize>,
+    top: usize,
+    preflows: [Preflow<F>; 2],
+    // visited marks for `cut`, all false in between
+    mark: Vec<bool>,
+    // flows multiplied by a scale fit in `F` if so does the scale times this
+    unit: Option<F>,
+}
+
+impl<F> TemporaryData<F> {
+    fn side(&self, v: usize, reverse: bool) -> Side {
+        let side = if self.in_source_side[v] {
+            Side::Source
+        } else if self.owner[v] == self.top {
+            Side::Inner
+        } else {
+            Side::Sink
+        };
+        match (side, reverse) {
+            (Side::Source, true) => Side::Sink,
+            (Side::Sink, true) => Side::Source,
+            _ => side,
+        }
+    }
+}
+
+/// Max flow whose capacities are linear functions `constant + slope * lambda` of a parameter,
+/// where only edges leaving the source may increase and only edges entering the sink may
+/// decrease in lambda.
+///
+/// Minimal min cuts are then nested in lambda, and `solve` finds every breakpoint of the min cut
+/// capacity by intersecting the capacities of known cuts, as Gallo, Grigoriadis and Tarjan.
+/// Each subproblem is the vertices between two known cuts, and is solved by FIFO push-relabel
+/// on the graph and on the reverse graph in turns until either finishes. The run on the graph is
+/// kept for the larger lambda side and the one on the reverse graph for the smaller side, with
+/// their preflows and labels, so only the other run of each subproblem starts from scratch.
+/// A probe thus costs at most twice the faster run, though without dynamic trees this does not
+/// reach the time bound of GGT.
+///
+/// Flows are kept exact by multiplying them by a common denominator of the lambda so far, and a
+/// run starts over if it would overflow.
+pub struct ParametricMaxFlow<F: Flow> {
+    edges: Vec<Edge<F>>,
+}
+
+pub struct Ret<F: Flow + Integer> {
+    breakpoints: Vec<Ratio<F>>,
+    lines: Vec<(F, F)>,
+    levels: Vec<usize>,
+}
+impl<F: Flow + Integer> Ret<F> {
+    /// Breakpoints of the min cut capacity in increasing order.
+    pub fn breakpoints(&self) -> &[Ratio<F>] {
+        &self.breakpoints
+    }
+
+    /// Number of linear pieces, i.e. `breakpoints().len() + 1`.
+    pub fn num_pieces(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// `(constant, slope)` of the min cut capacity on the `i`-th piece.
+    pub fn get_cut_capacity(&self, i: usize) -> (F, F) {
+        self.lines[i]
+    }
+
+    /// Minimal source side of min cuts on the `i`-th piece, which grows with `i`.
+    pub fn get_cut(&self, i: usize) -> Vec<usize> {
+        (0..self.levels.len())
+            .filter(|&v| self.levels[v] <= i)
+            .collect()
+    }
+
+    /// Min cut capacity at `lambda`.
+    pub fn get_value(&self, lambda: &Ratio<F>) -> Ratio<F> {
+        let i = self.breakpoints.partition_point(|b| b < lambda);
+        let (a, b) = self.lines[i];
+        lambda * b + a
+    }
+
+    /// Index of the first piece whose minimal min cut has `v` in its source side.
+    pub fn get_level(&self, v: usize) -> Option<usize> {
+        self.levels
+            .get(v)
+            .copied()
+            .filter(|&l| l < self.lines.len())
+    }
+}
+
+impl<F: Flow + Integer> Default for ParametricMaxFlow<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Flow + Integer> ParametricMaxFlow<F> {
+    pub fn new() -> Self {
+        Self { edges: Vec::new() }
+    }
+
+    pub fn add_edge(&mut self, src: usize, dst: usize, capacity: F) {
+        self.add_parametric_edge(src, dst, capacity, <F as Zero>::zero());
+    }
+
+    /// Adds an edge of capacity `constant + slope * lambda`.
+    /// `solve` requires it to leave the source if `slope` is positive, and to enter the sink if
+    /// `slope` is negative.
+    pub fn add_parametric_edge(&mut self, src: usize, dst: usize, constant: F, slope: F) {
+        self.edges.push(Edge {
+            src,
+            dst,
+            constant,
+            slope,
+        });
+    }
+}
+
+impl<F: Flow + Integer + CheckedMul + CheckedAdd> ParametricMaxFlow<F> {
+    fn prepare_data(&self, n: usize, bound: F) -> TemporaryData<F> {
+        let mut out_edges = vec![Vec::new(); n];
+        let mut in_edges = vec![Vec::new(); n];
+        let mut incident = vec![Vec::new(); n];
+        for (i, e) in self.edges.iter().enumerate() {
+            out_edges[e.src].push(i);
+            in_edges[e.dst].push(i);
+            if e.src != e.dst {
+                incident[e.src].push(i);
+                incident[e.dst].push(i);
+            }
+        }
+        let zero = <F as Zero>::zero();
+        let preflow = || Preflow {
+            flow: vec![zero; self.edges.len()],
+            excess: vec![zero; n],
+            label: vec![0; n],
+            current: vec![0; n],
+            queued: vec![false; n],
+        };
+        let unit = self.edges.iter().try_fold(zero, |unit, e| {
+            unit.checked_add(&e.constant.abs())?
+                .checked_add(&e.slope.abs().checked_mul(&bound)?)
+        });
+        TemporaryData {
+            out_edges,
+            in_edges,
+            incident,
+            in_source_side: vec![false; n],
+            owner: vec![!0; n],
+            top: 0,
+            preflows: [preflow(), preflow()],
+            mark: vec![false; n],
+            unit,
+        }
+    }
+
+    /// The other end of `eid` from `v` in the run, its side and the residual capacity to it,
+    /// unless it is the source or the edge comes from the source or the sink.
+    fn residual(
+        &self,
+        data: &TemporaryData<F>,
+        run: &Run<F>,
+        eid: usize,
+        v: usize,
+    ) -> Option<(usize, Side, F)> {
+        let e = &self.edges[eid];
+        let (tail, head) = e.ends(run.reverse);
+        let flow = data.preflows[run.reverse as usize].flow[eid];
+        if tail == v {
+            match data.side(head, run.reverse) {
+                Side::Source => None,
+                side => Some((head, side, run.capacity(e) - flow)),
+            }
+        } else if data.side(tail, run.reverse) == Side::Inner {
+            Some((tail, Side::Inner, flow))
+        } else {
+            None
+        }
+    }
+
+    /// Starts a run on `vertices`, which should be the inner ones, by saturating the edges
+    /// from the source.
+    fn fresh(
+        &self,
+        data: &mut TemporaryData<F>,
+        reverse: bool,
+        vertices: Vec<usize>,
+        p: F,
+        q: F,
+    ) -> Run<F> {
+        let zero = <F as Zero>::zero();
+        let mut run = Run {
+            reverse,
+            vertices,
+            p,
+            q,
+            scale: q,
+            queue: VecDeque::new(),
+        };
+        let r = reverse as usize;
+        for &v in &run.vertices {
+            data.preflows[r].excess[v] = zero;
+        }
+        for &v in &run.vertices {
+            for &eid in &data.incident[v] {
+                let e = &self.edges[eid];
+                let (tail, _) = e.ends(reverse);
+                if tail == v {
+                    data.preflows[r].flow[eid] = zero;
+                } else if data.side(tail, reverse) == Side::Source {
+                    data.preflows[r].excess[v] += run.capacity(e);
+                }
+            }
+        }
+        self.relabel_all(data, &mut run);
+        run
+    }
+
+    /// Sets the labels to the distances to the sink in the residual graph.
+    fn relabel_all(&self, data: &mut TemporaryData<F>, run: &mut Run<F>) {
+        let zero = <F as Zero>::zero();
+        let (n, r) = (run.n(), run.reverse as usize);
+        let mut queue = VecDeque::new();
+        for &v in &run.vertices {
+            data.preflows[r].current[v] = 0;
+            let to_sink = data.incident[v].iter().any(|&eid| {
+                matches!(self.residual(data, run, eid, v), Some((_, Side::Sink, c)) if c > zero)
+            });
+            data.preflows[r].label[v] = if to_sink {
+                queue.push_back(v);
+                1
+            } else {
+                n
+            };
+        }
+        while let Some(w) = queue.pop_front() {
+            for &eid in &data.incident[w] {
+                let e = &self.edges[eid];
+                let x = if e.src == w { e.dst } else { e.src };
+                if data.side(x, run.reverse) != Side::Inner || data.preflows[r].label[x] != n {
+                    continue;
+                }
+                if let Some((_, _, c)) = self.residual(data, run, eid, x) {
+                    if c > zero {
+                        data.preflows[r].label[x] = data.preflows[r].label[w] + 1;
+                        queue.push_back(x);
+                    }
+                }
+            }
+        }
+        self.requeue(data, run);
+    }
+
+    /// Rebuilds the queue of the active vertices.
+    fn requeue(&self, data: &mut TemporaryData<F>, run: &mut Run<F>) {
+        let zero = <F as Zero>::zero();
+        let n = run.n();
+        let pf = &mut data.preflows[run.reverse as usize];
+        run.queue.clear();
+        for &v in &run.vertices {
+            pf.queued[v] = pf.excess[v] > zero && pf.label[v] < n;
+            if pf.queued[v] {
+                run.queue.push_back(v);
+            }
+        }
+    }
+
+    /// Pushes the excess of `v` along admissible arcs until none is left or `v` is relabeled.
+    fn discharge(&self, data: &mut TemporaryData<F>, run: &mut Run<F>, v: usize) {
+        let zero = <F as Zero>::zero();
+        let (n, r) = (run.n(), run.reverse as usize);
+        while data.preflows[r].excess[v] > zero {
+            let eid = match data.incident[v].get(data.preflows[r].current[v]) {
+                Some(&eid) => eid,
+                None => {
+                    let pf = &data.preflows[r];
+                    let label = data.incident[v]
+                        .iter()
+                        .filter_map(|&eid| match self.residual(data, run, eid, v)? {
+                            (_, Side::Sink, c) if c > zero => Some(1),
+                            (w, Side::Inner, c) if c > zero => Some(pf.label[w] + 1),
+                            _ => None,
+                        })
+                        .fold(n, min);
+                    let pf = &mut data.preflows[r];
+                    pf.label[v] = label;
+                    pf.current[v] = 0;
+                    if label < n {
+                        pf.queued[v] = true;
+                        run.queue.push_back(v);
+                    }
+                    return;
+                }
+            };
+            let admissible = match self.residual(data, run, eid, v) {
+                Some((w, side, c)) if c > zero => {
+                    let label = &data.preflows[r].label;
+                    let target = if side == Side::Inner { label[w] + 1 } else { 1 };
+                    Some((w, side, c)).filter(|_| label[v] == target)
+                }
+                _ => None,
+            };
+            let pf = &mut data.preflows[r];
+            let (w, side, c) = match admissible {
+                Some(arc) => arc,
+                None => {
+                    pf.current[v] += 1;
+                    continue;
+                }
+            };
+            let amount = min(pf.excess[v], c);
+            if self.edges[eid].ends(run.reverse).0 == v {
+                pf.flow[eid] += amount;
+            } else {
+                pf.flow[eid] -= amount;
+            }
+            pf.excess[v] -= amount;
+            if side == Side::Inner {
+                pf.excess[w] += amount;
+                if !pf.queued[w] {
+                    pf.queued[w] = true;
+                    run.queue.push_back(w);
+                }
+            }
+        }
+    }
+
+    /// Discharges the next active vertex, or returns `false` if there is none, i.e. the
+    /// preflow is maximum.
+    fn step(&self, data: &mut TemporaryData<F>, run: &mut Run<F>) -> bool {
+        let zero = <F as Zero>::zero();
+        let (n, r) = (run.n(), run.reverse as usize);
+        while let Some(v) = run.queue.pop_front() {
+            let pf = &mut data.preflows[r];
+            pf.queued[v] = false;
+            if pf.excess[v] > zero && pf.label[v] < n {
+                self.discharge(data, run, v);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Moves a run to lambda = p / q, which should be larger than before on the graph itself
+    /// and smaller on the reverse graph. The edges from the source stay saturated and those to
+    /// the sink are cut down to their capacities, which keeps the labels valid.
+    fn advance(&self, data: &mut TemporaryData<F>, run: &mut Run<F>, p: F, q: F) {
+        let zero = <F as Zero>::zero();
+        let scale = run.scale.lcm(&q);
+        if data
+            .unit
+            .and_then(|unit| unit.checked_mul(&scale))
+            .is_none()
+        {
+            let vertices = mem::take(&mut run.vertices);
+            *run = self.fresh(data, run.reverse, vertices, p, q);
+            return;
+        }
+        let factor = scale / run.scale;
+        let (old_p, old_q) = (run.p, run.q);
+        run.p = p;
+        run.q = q;
+        run.scale = scale;
+        let r = run.reverse as usize;
+        for &v in &run.vertices {
+            data.preflows[r].excess[v] = data.preflows[r].excess[v] * factor;
+            for &eid in &data.incident[v] {
+                let e = &self.edges[eid];
+                let (tail, _) = e.ends(run.reverse);
+                let pf = &mut data.preflows[r];
+                if tail == v {
+                    let capacity = run.capacity(e);
+                    pf.flow[eid] = pf.flow[eid] * factor;
+                    if pf.flow[eid] > capacity {
+                        pf.excess[v] += pf.flow[eid] - capacity;
+                        pf.flow[eid] = capacity;
+                    }
+                } else if e.slope != zero && data.side(tail, run.reverse) == Side::Source {
+                    let increase = run.capacity(e) - e.capacity(old_p, old_q, scale);
+                    data.preflows[r].excess[v] += increase;
+                }
+            }
+        }
+        self.requeue(data, run);
+    }
+
+    /// Removes `removed` from a run and moves them to its source, keeping the vertices owned by
+    /// `keep`.
+    fn restrict(
+        &self,
+        data: &mut TemporaryData<F>,
+        run: &mut Run<F>,
+        keep: usize,
+        removed: &[usize],
+    ) {
+        let r = run.reverse as usize;
+        for &u in removed {
+            for &eid in &data.incident[u] {
+                let e = &self.edges[eid];
+                let (tail, head) = e.ends(run.reverse);
+                let w = if tail == u { head } else { tail };
+                if data.in_source_side[w] || data.owner[w] != keep {
+                    continue;
+                }
+                let pf = &mut data.preflows[r];
+                if tail == u {
+                    pf.excess[w] += run.capacity(e) - pf.flow[eid];
+                } else {
+                    pf.excess[w] += pf.flow[eid];
+                    pf.flow[eid] = <F as Zero>::zero();
+                }
+            }
+        }
+        let owner = &data.owner;
+        run.vertices.retain(|&v| owner[v] == keep);
+        self.requeue(data, run);
+    }
+
+    /// Vertices of a finished run in the minimal source side of min cuts.
+    fn cut(&self, data: &mut TemporaryData<F>, run: &mut Run<F>) -> Vec<usize> {
+        let zero = <F as Zero>::zero();
+        let r = run.reverse as usize;
+        if run.reverse {
+            // the vertices reaching the sink of the reverse graph, i.e. reached from the source
+            self.relabel_all(data, run);
+            let n = run.n();
+            return run
+                .vertices
+                .iter()
+                .copied()
+                .filter(|&v| data.preflows[r].label[v] < n)
+                .collect();
+        }
+        // the vertices left with excess and those they reach, which would be reached from the
+        // source once the excess is returned to it
+        let mut cut: Vec<_> = run
+            .vertices
+            .iter()
+            .copied()
+            .filter(|&v| data.preflows[r].excess[v] > zero)
+            .collect();
+        for &v in &cut {
+            data.mark[v] = true;
+        }
+        let mut i = 0;
+        while let Some(&x) = cut.get(i) {
+            i += 1;
+            for &eid in &data.incident[x] {
+                if let Some((y, Side::Inner, c)) = self.residual(data, run, eid, x) {
+                    if c > zero && !data.mark[y] {
+                        data.mark[y] = true;
+                        cut.push(y);
+                    }
+                }
+            }
+        }
+        for &v in &cut {
+            data.mark[v] = false;
+        }
+        cut
+    }
+
+    /// Capacity of the cut obtained by moving `added` into the current source side.
+    fn extend_line(&self, data: &mut TemporaryData<F>, (a, b): (F, F), added: &[usize]) -> (F, F) {
+        let (mut a, mut b) = (a, b);
+        for &v in added {
+            for &eid in &data.in_edges[v] {
+                let e = &self.edges[eid];
+                if data.in_source_side[e.src] {
+                    a -= e.constant;
+                    b -= e.slope;
+                }
+            }
+        }
+        for &v in added {
+            data.in_source_side[v] = true;
+        }
+        for &v in added {
+            for &eid in &data.out_edges[v] {
+                let e = &self.edges[eid];
+                if !data.in_source_side[e.dst] {
+                    a += e.constant;
+                    b += e.slope;
+                }
+            }
+        }
+        for &v in added {
+            data.in_source_side[v] = false;
+        }
+        (a, b)
+    }
+
+    /// Computes breakpoints of the min cut capacity and the minimal min cuts for lambda in
+    /// `[lower, upper]`.
+    pub fn solve(&self, s: usize, t: usize, lower: F, upper: F) -> Ret<F> {
+        assert_ne!(s, t, "Source and sink vertex should be different");
+        assert!(
+            lower <= upper,
+            "lower {} should be less or equal to upper {}",
+            lower,
+            upper
+        );
+        let zero = <F as Zero>::zero();
+        let one = <F as One>::one();
+        for e in &self.edges {
+            assert!(
+                e.slope == zero || (e.src == s && e.slope > zero) || (e.dst == t && e.slope < zero),
+                "Only source edges can increase and sink edges can decrease, but {} -> {} has slope {}",
+                e.src,
+                e.dst,
+                e.slope
+            );
+            assert!(
+                e.scaled_capacity(lower, one) >= zero && e.scaled_capacity(upper, one) >= zero,
+                "Capacity of {} -> {} should be non-negative in the range",
+                e.src,
+                e.dst
+            );
+        }
+        let n = self
+            .edges
+            .iter()
+            .fold(max(s, t) + 1, |n, e| max(n, max(e.src, e.dst) + 1));
+        let mut data = self.prepare_data(n, max(lower.abs(), upper.abs()));
+
+        let mut levels = vec![!0; n];
+        let mut breakpoints = Vec::new();
+        let mut lines = Vec::new();
+
+        let all: Vec<_> = (0..n).filter(|&v| v != s && v != t).collect();
+        let mut line = self.extend_line(&mut data, (zero, zero), &[s]);
+        data.in_source_side[s] = true;
+        levels[s] = 0;
+        for &v in &all {
+            data.owner[v] = 0;
+        }
+        let mut run = self.fresh(&mut data, true, all, lower, one);
+        while self.step(&mut data, &mut run) {}
+        let lower_cut = self.cut(&mut data, &mut run);
+        line = self.extend_line(&mut data, line, &lower_cut);
+        for &v in &lower_cut {
+            data.in_source_side[v] = true;
+            levels[v] = 0;
+        }
+        let rest: Vec<_> = run
+            .vertices
+            .into_iter()
+            .filter(|&v| !data.in_source_side[v])
+            .collect();
+        let mut run = self.fresh(&mut data, true, rest, upper, one);
+        while self.step(&mut data, &mut run) {}
+        let upper_cut = self.cut(&mut data, &mut run);
+        let upper_line = self.extend_line(&mut data, line, &upper_cut);
+        for &v in &run.vertices {
+            data.owner[v] = !0;
+        }
+        for &v in &upper_cut {
+            data.owner[v] = 0;
+        }
+        let removed: Vec<_> = run
+            .vertices
+            .iter()
+            .copied()
+            .filter(|&v| data.owner[v] != 0)
+            .collect();
+        self.restrict(&mut data, &mut run, 0, &removed);
+
+        let mut pending = vec![Cut {
+            line: upper_line,
+            diff: upper_cut,
+            run: Some(run),
+        }];
+        while let Some(id) = pending.len().checked_sub(1) {
+            let top = &mut pending[id];
+            let (a, b) = line;
+            let (ta, tb) = top.line;
+            if b != tb {
+                let lambda = Ratio::new(ta - a, b - tb);
+                let (p, q) = (*lambda.numer(), *lambda.denom());
+                data.top = id;
+                let mut inherited = top.run.take().unwrap();
+                self.advance(&mut data, &mut inherited, p, q);
+                let other = self.fresh(&mut data, !inherited.reverse, top.diff.clone(), p, q);
+                let (mut forward, mut backward) = if inherited.reverse {
+                    (other, inherited)
+                } else {
+                    (inherited, other)
+                };
+                let added = loop {
+                    if !self.step(&mut data, &mut forward) {
+                        break self.cut(&mut data, &mut forward);
+                    }
+                    if !self.step(&mut data, &mut backward) {
+                        break self.cut(&mut data, &mut backward);
+                    }
+                };
+                let new_line = self.extend_line(&mut data, line, &added);
+                if new_line.0 * q + new_line.1 * p < a * q + b * p {
+                    for &v in &added {
+                        data.owner[v] = id + 1;
+                    }
+                    let owner = &data.owner;
+                    top.diff.retain(|&v| owner[v] == id);
+                    self.restrict(&mut data, &mut forward, id, &added);
+                    self.restrict(&mut data, &mut backward, id + 1, &top.diff);
+                    top.run = Some(forward);
+                    pending.push(Cut {
+                        line: new_line,
+                        diff: added,
+                        run: Some(backward),
+                    });
+                    continue;
+                }
+                lines.push(line);
+                breakpoints.push(lambda);
+            }
+            let top = pending.pop().unwrap();
+            for v in top.diff {
+                data.in_source_side[v] = true;
+                levels[v] = lines.len();
+            }
+            line = top.line;
+        }
+        lines.push(line);
+        Ret {
+            breakpoints,
+            lines,
+            levels,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::flows::dinic::Dinic;
+
+    #[test]
+    fn test() {
+        // A 4-clique {0, 1, 2, 3} plus a path 3 - 4 - 5. Cutting an edge costs lambda and
+        // taking a vertex costs 2 - lambda, so the source side is the densest subgraph.
+        let vertices = 6;
+        let graph = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+        ];
+        let s = vertices + graph.len();
+        let t = s + 1;
+        let mut pmf = ParametricMaxFlow::new();
+        for (i, &(u, v)) in graph.iter().enumerate() {
+            pmf.add_parametric_edge(s, vertices + i, 0, 1);
+            pmf.add_edge(vertices + i, u, 1);
+            pmf.add_edge(vertices + i, v, 1);
+        }
+        for v in 0..vertices {
+            pmf.add_parametric_edge(v, t, 2, -1);
+        }
+        let ret = pmf.solve(s, t, 0, 2);
+        assert_eq!(ret.breakpoints(), &[Ratio::new(4, 5), Ratio::new(1, 1)][..]);
+        assert_eq!(ret.get_cut(0), vec![s]);
+        let mut cut = ret.get_cut(1);
+        cut.retain(|&v| v < vertices);
+        assert_eq!(cut, vec![0, 1, 2, 3]);
+        let mut cut = ret.get_cut(2);
+        cut.retain(|&v| v < vertices);
+        assert_eq!(cut, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(ret.get_level(4), Some(2));
+        assert_eq!(ret.get_level(t), None);
+
+        for num in 0..=20 {
+            let lambda = Ratio::new(num, 10);
+            let (p, q) = (*lambda.numer(), *lambda.denom());
+            let mut dinic = Dinic::new();
+            for e in &pmf.edges {
+                dinic.add_edge(e.src, e.dst, e.scaled_capacity(p, q));
+            }
+            let (flow, _) = dinic.max_flow(s, t);
+            assert_eq!(ret.get_value(&lambda), Ratio::new(flow, q));
+        }
+    }
+
+    #[test]
+    fn test_against_max_flow() {
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut rnd = |m: i64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % m as u64) as i64
+        };
+        let (s, t) = (0, 1);
+        for _ in 0..200 {
+            let n = 2 + rnd(10) as usize;
+            let lower = rnd(3) - 1;
+            let upper = lower + 1 + rnd(5);
+            let mut pmf = ParametricMaxFlow::new();
+            pmf.add_edge(s, t, 0);
+            for v in 2..n {
+                if rnd(3) > 0 {
+                    let slope = rnd(4);
+                    pmf.add_parametric_edge(s, v, slope * max(-lower, 0) + rnd(6), slope);
+                }
+                if rnd(3) > 0 {
+                    let slope = rnd(4);
+                    pmf.add_parametric_edge(v, t, slope * max(upper, 0) + rnd(6), -slope);
+                }
+            }
+            for _ in 0..rnd(3 * n as i64) {
+                pmf.add_edge(rnd(n as i64) as usize, rnd(n as i64) as usize, rnd(5));
+            }
+            let ret = pmf.solve(s, t, lower, upper);
+
+            let max_flow = |lambda: &Ratio<i64>| {
+                let (p, q) = (*lambda.numer(), *lambda.denom());
+                let mut dinic = Dinic::new();
+                for e in &pmf.edges {
+                    dinic.add_edge(e.src, e.dst, e.scaled_capacity(p, q));
+                }
+                let (flow, cut) = dinic.max_flow(s, t);
+                (Ratio::new(flow, q), cut)
+            };
+            let points: Vec<_> = Some(Ratio::from_integer(lower))
+                .into_iter()
+                .chain(ret.breakpoints().iter().copied())
+                .chain(Some(Ratio::from_integer(upper)))
+                .collect();
+            assert_eq!(ret.num_pieces(), points.len() - 1);
+            for (i, w) in points.windows(2).enumerate() {
+                assert!(w[0] <= w[1]);
+                let lambda = (w[0] + w[1]) / 2;
+                let (flow, cut) = max_flow(&lambda);
+                assert_eq!(ret.get_value(&lambda), flow);
+                assert_eq!(ret.get_cut(i), cut);
+                assert_eq!(max_flow(&w[1]).0, ret.get_value(&w[1]));
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+pub mod all_pairs_shortest_path;
+pub mod csr;
+pub mod directed_mst;
+pub mod dynamic_connectivity;
+pub mod lowlink;
+pub mod mst;
+pub mod offline_dynamic_connectivity;
+pub mod scc;
+pub mod shortest_path;
+pub mod two_sat;
+
+use std::fmt::Debug;
+
+/// An edge of an adjacency list as its source vertex and its index in the list of the source.
+pub type EdgeRef = (usize, usize);
+
+/// Directed graph on vertices `0..num_vertices()` with indexed access to the out-edges, so that
+/// a traversal can stop and resume at any edge.
+pub trait Graph {
+    type EdgeId: Copy + Eq + Debug;
+    type Weight;
+    fn num_vertices(&self) -> usize;
+    fn out_degree(&self, v: usize) -> usize;
+    /// The `i`-th out-edge of `v` as its id, destination and weight.
+    fn out_edge(&self, v: usize, i: usize) -> (Self::EdgeId, usize, &Self::Weight);
+    fn out_edges(&self, v: usize) -> OutEdges<'_, Self> {
+        OutEdges {
+            g: self,
+            v,
+            i: 0,
+            end: self.out_degree(v),
+        }
+    }
+}
+
+pub struct OutEdges<'a, G: ?Sized> {
+    g: &'a G,
+    v: usize,
+    i: usize,
+    end: usize,
+}
+
+impl<'a, G: Graph + ?Sized> Iterator for OutEdges<'a, G> {
+    type Item = (G::EdgeId, usize, &'a G::Weight);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i == self.end {
+            return None;
+        }
+        self.i += 1;
+        Some(self.g.out_edge(self.v, self.i - 1))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.end - self.i, Some(self.end - self.i))
+    }
+}
+
+macro_rules! implement_adjacency_list {
+    ($t:ty) => {
+        impl<W> Graph for $t {
+            type EdgeId = EdgeRef;
+            type Weight = W;
+            fn num_vertices(&self) -> usize {
+                self.len()
+            }
+            fn out_degree(&self, v: usize) -> usize {
+                self[v].len()
+            }
+            fn out_edge(&self, v: usize, i: usize) -> (EdgeRef, usize, &W) {
+                let (dst, ref w) = self[v][i];
+                ((v, i), dst, w)
+            }
+        }
+    };
+}
+implement_adjacency_list!([Vec<(usize, W)>]);
+implement_adjacency_list!(Vec<Vec<(usize, W)>>);
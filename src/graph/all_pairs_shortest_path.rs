@@ -0,0 +1,202 @@
+use crate::{
+    flows::Cost,
+    graph::{
+        shortest_path::{bellman_ford, dijkstra},
+        EdgeRef, Graph,
+    },
+};
+
+/// Distances between all pairs and the last edges of shortest paths.
+pub struct Ret<C, E = EdgeRef> {
+    dist: Vec<Vec<Option<C>>>,
+    // the previous vertex and the edge from there
+    parent: Vec<Vec<Option<(usize, E)>>>,
+}
+impl<C: Cost, E: Copy> Ret<C, E> {
+    pub fn get_dist(&self, s: usize, t: usize) -> Option<C> {
+        self.dist[s][t]
+    }
+    pub fn get_dists(&self, s: usize) -> &[Option<C>] {
+        &self.dist[s]
+    }
+    /// Edges of a shortest path from `s` to `t` in order, or `None` if `t` is unreachable.
+    pub fn get_path(&self, s: usize, t: usize) -> Option<Vec<E>> {
+        self.dist[s][t]?;
+        let mut path = Vec::new();
+        let mut v = t;
+        while v != s {
+            let (u, e) = self.parent[s][v].unwrap();
+            path.push(e);
+            v = u;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+type AdjacencyList<C> = Vec<Vec<(usize, C)>>;
+
+/// Copies `g` into an adjacency list with the costs mapped by `cost`, along with the ids of the
+/// edges at the same positions.
+fn to_adjacency_list<G, C, CF>(g: &G, mut cost: CF) -> (AdjacencyList<C>, Vec<Vec<G::EdgeId>>)
+where
+    G: Graph + ?Sized,
+    CF: FnMut(usize, usize, &G::Weight) -> C,
+{
+    (0..g.num_vertices())
+        .map(|u| {
+            g.out_edges(u)
+                .map(|(e, v, w)| ((v, cost(u, v, w)), e))
+                .unzip()
+        })
+        .unzip()
+}
+
+/// Bellman-Ford from a virtual vertex adjacent to every vertex, so that its distances are
+/// potentials making every cost non-negative, or a negative cycle as its edges in order.
+fn potential_or_negative_cycle<G, C>(g: &G) -> Result<Vec<C>, Vec<G::EdgeId>>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let n = g.num_vertices();
+    let (mut extended, ids) = to_adjacency_list(g, |_, _, &c| c);
+    extended.push((0..n).map(|v| (v, C::zero())).collect());
+    match bellman_ford(&extended, n) {
+        Ok(ret) => Ok((0..n).map(|v| ret.get_dist(v).unwrap()).collect()),
+        Err(cycle) => Err(cycle.into_iter().map(|(u, i)| ids[u][i]).collect()),
+    }
+}
+
+/// Floyd-Warshall algorithm in `O(n^3)` time. Returns a negative cycle as its edges in order if
+/// any.
+pub fn floyd_warshall<G, C>(g: &G) -> Result<Ret<C, G::EdgeId>, Vec<G::EdgeId>>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let n = g.num_vertices();
+    let mut dist = vec![vec![None; n]; n];
+    let mut parent = vec![vec![None; n]; n];
+    for u in 0..n {
+        dist[u][u] = Some(C::zero());
+        for (e, v, &c) in g.out_edges(u) {
+            match dist[u][v] {
+                Some(d) if d <= c => {}
+                _ => {
+                    dist[u][v] = Some(c);
+                    parent[u][v] = Some((u, e));
+                }
+            }
+        }
+    }
+    for k in 0..n {
+        for i in 0..n {
+            let dik = match dist[i][k] {
+                Some(d) => d,
+                None => continue,
+            };
+            for j in 0..n {
+                let dkj = match dist[k][j] {
+                    Some(d) => d,
+                    None => continue,
+                };
+                match dist[i][j] {
+                    Some(d) if d <= dik + dkj => {}
+                    _ => {
+                        dist[i][j] = Some(dik + dkj);
+                        parent[i][j] = parent[k][j];
+                    }
+                }
+            }
+            if dist[i][i].unwrap().is_negative() {
+                // the parents may be broken by now, so find a cycle afresh
+                return Err(potential_or_negative_cycle(g).err().unwrap());
+            }
+        }
+    }
+    Ok(Ret { dist, parent })
+}
+
+/// Johnson's algorithm in `O(nm + n(m + n log n))` time, i.e. Dijkstra from each vertex after
+/// making the costs non-negative by potentials from Bellman-Ford. Returns a negative cycle as its
+/// edges in order if any.
+pub fn johnson<G, C>(g: &G) -> Result<Ret<C, G::EdgeId>, Vec<G::EdgeId>>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let potential = potential_or_negative_cycle(g)?;
+    Ok(johnson_with_potential(g, &potential))
+}
+
+/// Johnson's algorithm with known potentials such that `cost + p[src] - p[dst]` is non-negative
+/// for every edge, e.g. the optimal potentials of a min cost flow on the graph.
+pub fn johnson_with_potential<G, C>(g: &G, potential: &[C]) -> Ret<C, G::EdgeId>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let (reduced, ids) = to_adjacency_list(g, |u, v, &c| {
+        let rc = c + potential[u] - potential[v];
+        assert!(
+            !rc.is_negative(),
+            "reduced cost {} should be non-negative",
+            rc
+        );
+        rc
+    });
+    let n = g.num_vertices();
+    let mut dist = Vec::with_capacity(n);
+    let mut parent = Vec::with_capacity(n);
+    for s in 0..n {
+        let ret = dijkstra(&reduced, s);
+        dist.push(
+            (0..n)
+                .map(|t| Some(ret.get_dist(t)? - potential[s] + potential[t]))
+                .collect(),
+        );
+        parent.push(
+            (0..n)
+                .map(|t| ret.get_parent(t).map(|(u, i)| (u, ids[u][i])))
+                .collect(),
+        );
+    }
+    Ret { dist, parent }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut g = vec![Vec::new(); 4];
+        for &(u, v, c) in &[(0, 1, 3), (1, 2, -2), (0, 2, 2), (2, 3, 2), (3, 0, -1)] {
+            g[u].push((v, c));
+        }
+        let expected = [
+            [Some(0), Some(3), Some(1), Some(3)],
+            [Some(-1), Some(0), Some(-2), Some(0)],
+            [Some(1), Some(4), Some(0), Some(2)],
+            [Some(-1), Some(2), Some(0), Some(0)],
+        ];
+        for ret in [floyd_warshall(&g).unwrap(), johnson(&g).unwrap()] {
+            for (s, row) in expected.iter().enumerate() {
+                assert_eq!(ret.get_dists(s), &row[..]);
+            }
+            assert_eq!(ret.get_path(0, 3), Some(vec![(0, 0), (1, 0), (2, 0)]));
+            assert_eq!(ret.get_path(2, 2), Some(vec![]));
+        }
+
+        g[2].push((1, 1));
+        for mut cycle in [
+            floyd_warshall(&g).err().unwrap(),
+            johnson(&g).err().unwrap(),
+        ] {
+            let start = cycle.iter().position(|e| e.0 == 1).unwrap();
+            cycle.rotate_left(start);
+            assert_eq!(cycle, vec![(1, 0), (2, 1)]);
+        }
+    }
+}
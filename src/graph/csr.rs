@@ -0,0 +1,243 @@
+use crate::{
+    flows::network::{MaxFlowEngine, MinCostFlowEngine},
+    graph::Graph,
+};
+
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Debug, Hash)]
+pub struct EdgeId(usize);
+impl EdgeId {
+    /// Position in the order the edges were added.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+pub struct CsrBuilder<W> {
+    n: usize,
+    // src, dst, weight, and the reverse edge or !0
+    edges: Vec<(usize, usize, W, usize)>,
+}
+
+impl<W> CsrBuilder<W> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_edge(&mut self, src: usize, dst: usize, weight: W) -> EdgeId {
+        assert!(
+            src < self.n && dst < self.n,
+            "Vertex ({}, {}) out of range {}",
+            src,
+            dst,
+            self.n
+        );
+        self.edges.push((src, dst, weight, !0));
+        EdgeId(self.edges.len() - 1)
+    }
+
+    /// Adds `src -> dst` and `dst -> src` as the reverse edges of each other.
+    pub fn add_edge_with_reverse(
+        &mut self,
+        src: usize,
+        dst: usize,
+        weight: W,
+        reverse_weight: W,
+    ) -> (EdgeId, EdgeId) {
+        let e = self.add_edge(src, dst, weight);
+        let r = self.add_edge(dst, src, reverse_weight);
+        self.edges[e.0].3 = r.0;
+        self.edges[r.0].3 = e.0;
+        (e, r)
+    }
+
+    /// Lays out the edges by counting sort on the sources, keeping the order of addition among
+    /// the out-edges of each vertex.
+    pub fn build(self) -> Csr<W> {
+        let n = self.n;
+        let m = self.edges.len();
+        let mut start = vec![0; n + 1];
+        let mut in_start = vec![0; n + 1];
+        for &(src, dst, _, _) in &self.edges {
+            start[src + 1] += 1;
+            in_start[dst + 1] += 1;
+        }
+        for v in 0..n {
+            start[v + 1] += start[v];
+            in_start[v + 1] += in_start[v];
+        }
+        let mut position = vec![0; m];
+        let mut in_edges = vec![EdgeId(0); m];
+        let mut next = start.clone();
+        let mut in_next = in_start.clone();
+        let mut endpoints = Vec::with_capacity(m);
+        let mut reverse = Vec::with_capacity(m);
+        for (i, &(src, dst, _, rev)) in self.edges.iter().enumerate() {
+            position[i] = next[src];
+            next[src] += 1;
+            in_edges[in_next[dst]] = EdgeId(i);
+            in_next[dst] += 1;
+            endpoints.push((src, dst));
+            reverse.push(rev);
+        }
+        let mut slots: Vec<Option<(EdgeId, usize, W)>> = (0..m).map(|_| None).collect();
+        for (i, (_, dst, w, _)) in self.edges.into_iter().enumerate() {
+            slots[position[i]] = Some((EdgeId(i), dst, w));
+        }
+        let (ids, targets, weights) = slots.into_iter().map(Option::unwrap).fold(
+            (
+                Vec::with_capacity(m),
+                Vec::with_capacity(m),
+                Vec::with_capacity(m),
+            ),
+            |(mut ids, mut targets, mut weights), (e, dst, w)| {
+                ids.push(e);
+                targets.push(dst);
+                weights.push(w);
+                (ids, targets, weights)
+            },
+        );
+        Csr {
+            start,
+            ids,
+            targets,
+            weights,
+            position,
+            endpoints,
+            reverse,
+            in_start,
+            in_edges,
+        }
+    }
+}
+
+/// Compressed sparse row graph, where the out-edges of each vertex are contiguous.
+pub struct Csr<W> {
+    start: Vec<usize>,
+    // by position
+    ids: Vec<EdgeId>,
+    targets: Vec<usize>,
+    weights: Vec<W>,
+    // by edge id
+    position: Vec<usize>,
+    endpoints: Vec<(usize, usize)>,
+    reverse: Vec<usize>,
+    // edge ids into each vertex
+    in_start: Vec<usize>,
+    in_edges: Vec<EdgeId>,
+}
+
+impl<W> Csr<W> {
+    pub fn num_edges(&self) -> usize {
+        self.ids.len()
+    }
+    pub fn src(&self, e: EdgeId) -> usize {
+        self.endpoints[e.0].0
+    }
+    pub fn dst(&self, e: EdgeId) -> usize {
+        self.endpoints[e.0].1
+    }
+    pub fn weight(&self, e: EdgeId) -> &W {
+        &self.weights[self.position[e.0]]
+    }
+    pub fn weight_mut(&mut self, e: EdgeId) -> &mut W {
+        &mut self.weights[self.position[e.0]]
+    }
+    /// The edge added together with `e` by [`CsrBuilder::add_edge_with_reverse`].
+    pub fn reverse(&self, e: EdgeId) -> Option<EdgeId> {
+        match self.reverse[e.0] {
+            r if r == !0 => None,
+            r => Some(EdgeId(r)),
+        }
+    }
+    /// All edges in the order they were added, with their sources and destinations.
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, usize, usize, &W)> + '_ {
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(move |(i, &(src, dst))| (EdgeId(i), src, dst, &self.weights[self.position[i]]))
+    }
+    pub fn in_edges(&self, v: usize) -> impl Iterator<Item = EdgeId> + '_ {
+        self.in_edges[self.in_start[v]..self.in_start[v + 1]]
+            .iter()
+            .cloned()
+    }
+
+    /// Adds the edges to a max flow engine, returning the ids of the engine indexed by
+    /// [`EdgeId::index`].
+    pub fn build_max_flow<F, E, CF>(&self, engine: &mut E, mut capacity: CF) -> Vec<E::EdgeId>
+    where
+        E: MaxFlowEngine<F>,
+        CF: FnMut(&W) -> F,
+    {
+        self.edges()
+            .map(|(_, src, dst, w)| engine.add_edge(src, dst, capacity(w)))
+            .collect()
+    }
+
+    /// Adds the edges to a min cost flow engine with `(lower, upper, cost)` given by `arc`,
+    /// returning the ids of the engine indexed by [`EdgeId::index`].
+    pub fn build_min_cost_flow<F, C, E, AF>(&self, engine: &mut E, mut arc: AF) -> Vec<E::EdgeId>
+    where
+        E: MinCostFlowEngine<F, C>,
+        AF: FnMut(&W) -> (F, F, C),
+    {
+        self.edges()
+            .map(|(_, src, dst, w)| {
+                let (lower, upper, cost) = arc(w);
+                engine.add_edge(src, dst, lower, upper, cost)
+            })
+            .collect()
+    }
+}
+
+impl<W> Graph for Csr<W> {
+    type EdgeId = EdgeId;
+    type Weight = W;
+    fn num_vertices(&self) -> usize {
+        self.start.len() - 1
+    }
+    fn out_degree(&self, v: usize) -> usize {
+        self.start[v + 1] - self.start[v]
+    }
+    fn out_edge(&self, v: usize, i: usize) -> (EdgeId, usize, &W) {
+        let p = self.start[v] + i;
+        (self.ids[p], self.targets[p], &self.weights[p])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{flows::dinic::Dinic, graph::shortest_path::dijkstra};
+
+    #[test]
+    fn test() {
+        let mut builder = CsrBuilder::new(4);
+        let e0 = builder.add_edge(2, 3, 4);
+        let (e1, r1) = builder.add_edge_with_reverse(0, 1, 1, 0);
+        let e2 = builder.add_edge(0, 2, 2);
+        let e3 = builder.add_edge(1, 3, 2);
+        let mut g = builder.build();
+        assert_eq!(g.num_vertices(), 4);
+        assert_eq!(g.num_edges(), 5);
+        let out: Vec<_> = g.out_edges(0).map(|(e, v, &w)| (e, v, w)).collect();
+        assert_eq!(out, vec![(e1, 1, 1), (e2, 2, 2)]);
+        assert_eq!(g.in_edges(3).collect::<Vec<_>>(), vec![e0, e3]);
+        assert_eq!((g.src(r1), g.dst(r1), g.reverse(r1)), (1, 0, Some(e1)));
+        assert_eq!(g.reverse(e0), None);
+
+        let ret = dijkstra(&g, 0);
+        assert_eq!(ret.get_dist(3), Some(3));
+        assert_eq!(ret.get_path(3), Some(vec![e1, e3]));
+        *g.weight_mut(e3) = 6;
+        assert_eq!(dijkstra(&g, 0).get_path(3), Some(vec![e2, e0]));
+
+        let mut dinic = Dinic::new();
+        let ids = g.build_max_flow(&mut dinic, |&w| w);
+        assert_eq!(dinic.max_flow(0, 3).0, 3);
+        assert_eq!(dinic.get_flow(&ids[e0.index()]), 2);
+    }
+}
@@ -0,0 +1,137 @@
+use std::mem;
+
+use crate::{
+    data_structures::{
+        skew_heap::{Heap, SkewHeap},
+        union_find::UnionFind,
+    },
+    flows::Cost,
+};
+
+/// Minimum spanning arborescence as the parent edge of each vertex.
+pub struct Ret<C> {
+    cost: C,
+    parent: Vec<Option<usize>>,
+}
+impl<C: Cost> Ret<C> {
+    pub fn get_cost(&self) -> C {
+        self.cost
+    }
+    /// Index of the edge into `v`, or `None` for the root.
+    pub fn get_parent(&self, v: usize) -> Option<usize> {
+        self.parent[v]
+    }
+    pub fn get_parents(&self) -> &[Option<usize>] {
+        &self.parent
+    }
+}
+
+/// Minimum spanning arborescence rooted at `root` of the graph given as `(src, dst, cost)` by
+/// Tarjan's algorithm on [`SkewHeap`] and [`UnionFind`] in `O(m log n)` time, or `None` if some
+/// vertex is unreachable from `root`.
+///
+/// Cycles of the cheapest incoming edges are contracted into new nodes, which form a forest over
+/// the vertices for expanding the chosen edges at the end.
+pub fn directed_mst<C: Cost>(n: usize, root: usize, edges: &[(usize, usize, C)]) -> Option<Ret<C>> {
+    let mut sh = SkewHeap::new();
+    let mut heap: Vec<Heap> = (0..n).map(|_| Heap::new()).collect();
+    for (e, &(src, dst, cost)) in edges.iter().enumerate() {
+        if src != dst {
+            sh.push(&mut heap[dst], cost, e);
+        }
+    }
+    let mut uf = UnionFind::new(n);
+    // the node of the forest for each component, its parent and the edge chosen into it
+    let mut node: Vec<usize> = (0..n).collect();
+    let mut forest_parent = vec![!0; n];
+    let mut incoming = vec![!0; n];
+    let mut seen = vec![!0; n];
+    seen[root] = root;
+    for s in 0..n {
+        let mut u = s;
+        let mut path = Vec::new();
+        while seen[u] == !0 {
+            seen[u] = s;
+            path.push(u);
+            let (cost, e) = loop {
+                let (cost, e) = sh.pop(&mut heap[u])?;
+                if uf.find_mut(edges[e].0) != u {
+                    break (cost, e);
+                }
+            };
+            sh.add(&heap[u], -cost);
+            incoming[node[u]] = e;
+            u = uf.find_mut(edges[e].0);
+            if seen[u] != s {
+                continue;
+            }
+            let x = forest_parent.len();
+            forest_parent.push(!0);
+            incoming.push(!0);
+            let mut cycle_heap = Heap::new();
+            let mut members = Vec::new();
+            loop {
+                let w = path.pop().unwrap();
+                cycle_heap = sh.meld(cycle_heap, mem::take(&mut heap[w]));
+                forest_parent[node[w]] = x;
+                members.push(w);
+                if w == u {
+                    break;
+                }
+            }
+            for w in members {
+                uf.unite(u, w);
+            }
+            u = uf.find_mut(u);
+            heap[u] = cycle_heap;
+            node[u] = x;
+            seen[u] = !0;
+        }
+    }
+
+    // a chosen edge into a node discards the edges chosen into the nodes containing its
+    // destination below, which have smaller indices
+    let mut discarded = vec![false; forest_parent.len()];
+    let mut parent = vec![None; n];
+    let mut cost = C::zero();
+    for x in (0..forest_parent.len()).rev() {
+        if x == root || discarded[x] {
+            continue;
+        }
+        let e = incoming[x];
+        let mut z = edges[e].1;
+        parent[z] = Some(e);
+        cost += edges[e].2;
+        while z != x {
+            discarded[z] = true;
+            z = forest_parent[z];
+        }
+    }
+    Some(Ret { cost, parent })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let edges = vec![
+            (0, 1, 10),
+            (1, 2, 1),
+            (2, 3, 1),
+            (3, 1, 1),
+            (0, 3, 8),
+            (2, 2, 0),
+            (3, 4, 5),
+            (2, 4, 4),
+        ];
+        let ret = directed_mst(5, 0, &edges).unwrap();
+        assert_eq!(ret.get_cost(), 14);
+        assert_eq!(
+            ret.get_parents(),
+            &[None, Some(3), Some(1), Some(4), Some(7)]
+        );
+        assert!(directed_mst(5, 1, &edges).is_none());
+    }
+}
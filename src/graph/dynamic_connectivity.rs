@@ -0,0 +1,281 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+use crate::data_structures::splay_tree::{
+    euler_tour_tree::{EulerTourArena, EulerTourTree},
+    SeqOps,
+};
+
+/// [`SeqOps`] of the flags of a vertex in a level, whether it has tree edges and non-tree edges
+/// of the level.
+pub enum LevelFlags {}
+impl SeqOps for LevelFlags {
+    type Value = (bool, bool);
+
+    type Acc = (bool, bool);
+
+    fn value_to_acc(val: &Self::Value) -> Self::Acc {
+        *val
+    }
+
+    fn identity() -> Self::Acc {
+        (false, false)
+    }
+
+    fn binary_operation(lhs: &Self::Acc, rhs: &Self::Acc) -> Self::Acc {
+        (lhs.0 || rhs.0, lhs.1 || rhs.1)
+    }
+}
+
+struct Edge {
+    level: usize,
+    tree: bool,
+    count: usize,
+}
+
+fn key(u: usize, v: usize) -> (usize, usize) {
+    match u.cmp(&v) {
+        Ordering::Greater => (v, u),
+        _ => (u, v),
+    }
+}
+
+/// Fully dynamic connectivity by Holm, de Lichtenberg and Thorup, in amortized `O(log^2 n)` time
+/// per update and `O(log n)` time per query.
+///
+/// Each edge has a level, which only increases. The forest of level `i` is a spanning forest of
+/// the edges of levels at least `i` kept by an [`EulerTourTree`], whose trees have at most
+/// `n / 2^i` vertices, and the forest of level 0 is a spanning forest of the graph. The levels
+/// are allocated as they are reached.
+pub struct DynamicConnectivity<'arena> {
+    arena: &'arena EulerTourArena<'arena, LevelFlags>,
+    n: usize,
+    forests: Vec<EulerTourTree<'arena, LevelFlags>>,
+    // the neighbors by the tree edges and the non-tree edges of each level
+    tree_adj: Vec<Vec<HashSet<usize>>>,
+    non_tree_adj: Vec<Vec<HashSet<usize>>>,
+    edges: HashMap<(usize, usize), Edge>,
+}
+
+impl<'arena> DynamicConnectivity<'arena> {
+    pub fn new(arena: &'arena EulerTourArena<'arena, LevelFlags>, n: usize) -> Self {
+        let mut ret = Self {
+            arena,
+            n,
+            forests: Vec::new(),
+            tree_adj: Vec::new(),
+            non_tree_adj: Vec::new(),
+            edges: HashMap::new(),
+        };
+        ret.reach_level(0);
+        ret
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    fn reach_level(&mut self, i: usize) {
+        while self.forests.len() <= i {
+            let values = (0..self.n).map(|_| (false, false));
+            self.forests.push(EulerTourTree::new(self.arena, values));
+            self.tree_adj.push(vec![HashSet::new(); self.n]);
+            self.non_tree_adj.push(vec![HashSet::new(); self.n]);
+        }
+    }
+
+    fn update_flags(&self, i: usize, v: usize) {
+        let flags = (
+            !self.tree_adj[i][v].is_empty(),
+            !self.non_tree_adj[i][v].is_empty(),
+        );
+        if *self.forests[i].get(v) != flags {
+            self.forests[i].set(v, flags);
+        }
+    }
+
+    fn insert_tree_edge(&mut self, i: usize, u: usize, v: usize) {
+        self.tree_adj[i][u].insert(v);
+        self.tree_adj[i][v].insert(u);
+        self.update_flags(i, u);
+        self.update_flags(i, v);
+    }
+
+    fn remove_tree_edge(&mut self, i: usize, u: usize, v: usize) {
+        self.tree_adj[i][u].remove(&v);
+        self.tree_adj[i][v].remove(&u);
+        self.update_flags(i, u);
+        self.update_flags(i, v);
+    }
+
+    fn insert_non_tree_edge(&mut self, i: usize, u: usize, v: usize) {
+        self.non_tree_adj[i][u].insert(v);
+        self.non_tree_adj[i][v].insert(u);
+        self.update_flags(i, u);
+        self.update_flags(i, v);
+    }
+
+    fn remove_non_tree_edge(&mut self, i: usize, u: usize, v: usize) {
+        self.non_tree_adj[i][u].remove(&v);
+        self.non_tree_adj[i][v].remove(&u);
+        self.update_flags(i, u);
+        self.update_flags(i, v);
+    }
+
+    /// Inserts an undirected edge, which may be parallel to others.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(
+            u < self.n && v < self.n,
+            "Vertex ({}, {}) out of range {}",
+            u,
+            v,
+            self.n
+        );
+        if let Some(edge) = self.edges.get_mut(&key(u, v)) {
+            edge.count += 1;
+            return;
+        }
+        let tree = u != v && !self.forests[0].connected(u, v);
+        if tree {
+            self.forests[0].link(u, v);
+            self.insert_tree_edge(0, u, v);
+        } else if u != v {
+            self.insert_non_tree_edge(0, u, v);
+        }
+        let edge = Edge {
+            level: 0,
+            tree,
+            count: 1,
+        };
+        self.edges.insert(key(u, v), edge);
+    }
+
+    /// Deletes one copy of an edge, which should exist.
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        let edge = self
+            .edges
+            .get_mut(&key(u, v))
+            .unwrap_or_else(|| panic!("Edge ({}, {}) does not exist", u, v));
+        edge.count -= 1;
+        if edge.count > 0 {
+            return;
+        }
+        let Edge { level, tree, .. } = self.edges.remove(&key(u, v)).unwrap();
+        if u == v {
+            return;
+        }
+        if !tree {
+            self.remove_non_tree_edge(level, u, v);
+            return;
+        }
+        self.remove_tree_edge(level, u, v);
+        for forest in &mut self.forests[..=level] {
+            forest.cut(u, v);
+        }
+        for i in (0..=level).rev() {
+            if self.replace(i, u, v) {
+                return;
+            }
+        }
+    }
+
+    /// Looks for a replacement of the cut tree edge between `u` and `v` among the non-tree edges
+    /// of level `i`, raising the levels of the edges in the smaller tree of level `i`.
+    fn replace(&mut self, i: usize, u: usize, v: usize) -> bool {
+        let forest = &self.forests[i];
+        let s = if forest.component_len(u) <= forest.component_len(v) {
+            u
+        } else {
+            v
+        };
+        // the smaller tree has at most n / 2^(i + 1) vertices, so fits in level i + 1
+        while let Some(x) = self.forests[i].find_vertex(s, |flags| flags.0) {
+            self.reach_level(i + 1);
+            let neighbors = std::mem::take(&mut self.tree_adj[i][x]);
+            self.update_flags(i, x);
+            for y in neighbors {
+                self.tree_adj[i][y].remove(&x);
+                self.update_flags(i, y);
+                self.edges.get_mut(&key(x, y)).unwrap().level = i + 1;
+                self.forests[i + 1].link(x, y);
+                self.insert_tree_edge(i + 1, x, y);
+            }
+        }
+        while let Some(x) = self.forests[i].find_vertex(s, |flags| flags.1) {
+            let neighbors: Vec<_> = std::mem::take(&mut self.non_tree_adj[i][x])
+                .into_iter()
+                .collect();
+            self.update_flags(i, x);
+            for (j, &y) in neighbors.iter().enumerate() {
+                self.non_tree_adj[i][y].remove(&x);
+                self.update_flags(i, y);
+                let edge = self.edges.get_mut(&key(x, y)).unwrap();
+                if self.forests[i].connected(y, s) {
+                    edge.level = i + 1;
+                    self.reach_level(i + 1);
+                    self.insert_non_tree_edge(i + 1, x, y);
+                } else {
+                    edge.tree = true;
+                    for forest in &mut self.forests[..=i] {
+                        forest.link(x, y);
+                    }
+                    self.insert_tree_edge(i, x, y);
+                    // the unprocessed edges stay at level i
+                    self.non_tree_adj[i][x].extend(&neighbors[j + 1..]);
+                    self.update_flags(i, x);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn connected(&self, u: usize, v: usize) -> bool {
+        self.forests[0].connected(u, v)
+    }
+
+    /// The number of vertices in the component of `v`.
+    pub fn component_size(&self, v: usize) -> usize {
+        self.forests[0].component_len(v)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::arena::Arena;
+
+    #[test]
+    fn test() {
+        let arena = Arena::new();
+        let mut dc = DynamicConnectivity::new(&arena, 6);
+        dc.add_edge(0, 1);
+        dc.add_edge(1, 2);
+        dc.add_edge(2, 0);
+        dc.add_edge(3, 4);
+        dc.add_edge(3, 4);
+        dc.add_edge(5, 5);
+        assert!(dc.connected(0, 2));
+        assert!(!dc.connected(2, 3));
+        assert_eq!(dc.component_size(1), 3);
+        dc.remove_edge(0, 1);
+        dc.remove_edge(2, 1);
+        assert!(dc.connected(0, 2) && !dc.connected(0, 1));
+        dc.remove_edge(4, 3);
+        assert!(dc.connected(3, 4));
+        dc.remove_edge(3, 4);
+        assert!(!dc.connected(3, 4));
+        dc.add_edge(2, 4);
+        dc.add_edge(4, 1);
+        dc.add_edge(1, 0);
+        dc.remove_edge(2, 4);
+        assert_eq!(dc.component_size(0), 4);
+        dc.remove_edge(5, 5);
+        assert_eq!(dc.component_size(5), 1);
+    }
+}
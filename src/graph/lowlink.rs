@@ -0,0 +1,212 @@
+use std::cmp::min;
+
+/// Bridges, articulation points, 2-edge-connected components and biconnected components of an
+/// undirected graph by lowlink in `O(n + m)` time.
+///
+/// Multi-edges are told apart by their ids, so parallel edges are never bridges, and self-loops
+/// belong to no block.
+pub struct Lowlink {
+    n: usize,
+    edges: Vec<(usize, usize)>,
+    is_bridge: Vec<bool>,
+    is_articulation_point: Vec<bool>,
+    two_edge_component: Vec<usize>,
+    num_two_edge_components: usize,
+    edge_block: Vec<Option<usize>>,
+    blocks: Vec<Vec<usize>>,
+}
+
+impl Lowlink {
+    pub fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adjacent = vec![Vec::new(); n];
+        for (i, &(u, v)) in edges.iter().enumerate() {
+            adjacent[u].push((v, i));
+            if u != v {
+                adjacent[v].push((u, i));
+            }
+        }
+        let mut ret = Self {
+            n,
+            edges: edges.to_vec(),
+            is_bridge: vec![false; edges.len()],
+            is_articulation_point: vec![false; n],
+            two_edge_component: vec![!0; n],
+            num_two_edge_components: 0,
+            edge_block: vec![None; edges.len()],
+            blocks: Vec::new(),
+        };
+
+        let mut ord = vec![!0; n];
+        let mut low = vec![0; n];
+        let mut parent_edge = vec![!0; n];
+        let mut counter = 0;
+        // vertex and the next adjacent edge to visit
+        let mut calls: Vec<(usize, usize)> = Vec::new();
+        let mut vertex_stack = Vec::new();
+        let mut edge_stack = Vec::new();
+        for r in 0..n {
+            if ord[r] != !0 {
+                continue;
+            }
+            ord[r] = counter;
+            low[r] = counter;
+            counter += 1;
+            vertex_stack.push(r);
+            calls.push((r, 0));
+            let mut root_children = 0;
+            while let Some((v, i)) = calls.last_mut() {
+                let v = *v;
+                if let Some(&(w, e)) = adjacent[v].get(*i) {
+                    *i += 1;
+                    if e == parent_edge[v] {
+                        continue;
+                    }
+                    if ord[w] == !0 {
+                        ord[w] = counter;
+                        low[w] = counter;
+                        counter += 1;
+                        parent_edge[w] = e;
+                        vertex_stack.push(w);
+                        edge_stack.push(e);
+                        calls.push((w, 0));
+                    } else if ord[w] < ord[v] {
+                        // back edge to an ancestor, which is seen once from the descendant
+                        low[v] = min(low[v], ord[w]);
+                        edge_stack.push(e);
+                    }
+                    continue;
+                }
+                calls.pop();
+                let p = match calls.last() {
+                    Some(&(p, _)) => p,
+                    None => break,
+                };
+                low[p] = min(low[p], low[v]);
+                if low[v] > ord[p] {
+                    ret.is_bridge[parent_edge[v]] = true;
+                    loop {
+                        let u = vertex_stack.pop().unwrap();
+                        ret.two_edge_component[u] = ret.num_two_edge_components;
+                        if u == v {
+                            break;
+                        }
+                    }
+                    ret.num_two_edge_components += 1;
+                }
+                if low[v] >= ord[p] {
+                    // p separates the subtree of v
+                    if p == r {
+                        root_children += 1;
+                    } else {
+                        ret.is_articulation_point[p] = true;
+                    }
+                    let block = ret.blocks.len();
+                    let mut vertices = Vec::new();
+                    loop {
+                        let e = edge_stack.pop().unwrap();
+                        ret.edge_block[e] = Some(block);
+                        vertices.push(edges[e].0);
+                        vertices.push(edges[e].1);
+                        if e == parent_edge[v] {
+                            break;
+                        }
+                    }
+                    vertices.sort_unstable();
+                    vertices.dedup();
+                    ret.blocks.push(vertices);
+                }
+            }
+            if root_children >= 2 {
+                ret.is_articulation_point[r] = true;
+            }
+            if root_children == 0 {
+                ret.blocks.push(vec![r]);
+            }
+            while let Some(u) = vertex_stack.pop() {
+                ret.two_edge_component[u] = ret.num_two_edge_components;
+            }
+            ret.num_two_edge_components += 1;
+        }
+        ret
+    }
+
+    pub fn is_bridge(&self, e: usize) -> bool {
+        self.is_bridge[e]
+    }
+    pub fn bridges(&self) -> Vec<usize> {
+        (0..self.edges.len())
+            .filter(|&e| self.is_bridge[e])
+            .collect()
+    }
+    pub fn is_articulation_point(&self, v: usize) -> bool {
+        self.is_articulation_point[v]
+    }
+    pub fn articulation_points(&self) -> Vec<usize> {
+        (0..self.n)
+            .filter(|&v| self.is_articulation_point[v])
+            .collect()
+    }
+
+    pub fn num_two_edge_connected_components(&self) -> usize {
+        self.num_two_edge_components
+    }
+    pub fn get_two_edge_connected_component(&self, v: usize) -> usize {
+        self.two_edge_component[v]
+    }
+    pub fn two_edge_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.num_two_edge_components];
+        for (v, &c) in self.two_edge_component.iter().enumerate() {
+            components[c].push(v);
+        }
+        components
+    }
+
+    /// Biconnected components as sorted vertex lists, where a vertex without edges other than
+    /// self-loops forms a block by itself.
+    pub fn biconnected_components(&self) -> &[Vec<usize>] {
+        &self.blocks
+    }
+    /// The block containing the edge, or `None` for a self-loop.
+    pub fn get_edge_block(&self, e: usize) -> Option<usize> {
+        self.edge_block[e]
+    }
+
+    /// Forest on the vertices `0..n` and the blocks `n..n + num_blocks`, joining each block to
+    /// its vertices. Articulation points are exactly the vertices of degree at least 2.
+    pub fn block_cut_tree(&self) -> Vec<Vec<usize>> {
+        let mut tree = vec![Vec::new(); self.n + self.blocks.len()];
+        for (b, vertices) in self.blocks.iter().enumerate() {
+            for &v in vertices {
+                tree[v].push(self.n + b);
+                tree[self.n + b].push(v);
+            }
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        // triangle 0-1-2 with a double edge 2=3, a bridge 3-4, a self-loop on 4 and isolated 5
+        let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 2), (3, 4), (4, 4)];
+        let ll = Lowlink::new(6, &edges);
+        assert_eq!(ll.bridges(), vec![5]);
+        assert_eq!(ll.articulation_points(), vec![2, 3]);
+        let mut components = ll.two_edge_connected_components();
+        components.sort();
+        assert_eq!(components, vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+        let mut blocks = ll.biconnected_components().to_vec();
+        blocks.sort();
+        assert_eq!(blocks, vec![vec![0, 1, 2], vec![2, 3], vec![3, 4], vec![5]]);
+        assert_eq!(ll.get_edge_block(6), None);
+        assert_eq!(ll.get_edge_block(3), ll.get_edge_block(4));
+        let tree = ll.block_cut_tree();
+        assert_eq!(tree.len(), 10);
+        let cut: Vec<_> = (0..6).filter(|&v| tree[v].len() >= 2).collect();
+        assert_eq!(cut, ll.articulation_points());
+    }
+}
@@ -0,0 +1,295 @@
+use std::mem::swap;
+
+use crate::{
+    data_structures::union_find::UnionFind,
+    flows::Cost,
+    graph::shortest_path::{IndexedLazyHollowHeap, VertexHeap},
+};
+
+/// Minimum spanning forest of an undirected graph given as `(u, v, cost)`, whose edges are
+/// referred to by their indices.
+///
+/// Ties are broken by the indices, so all the algorithms choose the same forest.
+pub struct Ret<C> {
+    cost: C,
+    edges: Vec<usize>,
+}
+impl<C: Cost> Ret<C> {
+    pub fn get_cost(&self) -> C {
+        self.cost
+    }
+    /// Indices of the chosen edges in the ascending order.
+    pub fn get_edges(&self) -> &[usize] {
+        &self.edges
+    }
+}
+
+fn make_ret<C: Cost>(edges: &[(usize, usize, C)], mut chosen: Vec<usize>) -> Ret<C> {
+    chosen.sort_unstable();
+    let mut cost = C::zero();
+    for &e in &chosen {
+        cost += edges[e].2;
+    }
+    Ret {
+        cost,
+        edges: chosen,
+    }
+}
+
+/// Kruskal's algorithm in `O(m log m)` time.
+pub fn kruskal<C: Cost>(n: usize, edges: &[(usize, usize, C)]) -> Ret<C> {
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_unstable_by_key(|&e| (edges[e].2, e));
+    let mut uf = UnionFind::new(n);
+    let chosen = order
+        .into_iter()
+        .filter(|&e| uf.unite(edges[e].0, edges[e].1).0)
+        .collect();
+    make_ret(edges, chosen)
+}
+
+/// Prim's algorithm on [`LazyHollowHeap`](crate::data_structures::lazy_hollow_heap::LazyHollowHeap)
+/// in `O(m + n log n)` time.
+pub fn prim<C: Cost>(n: usize, edges: &[(usize, usize, C)]) -> Ret<C> {
+    let mut adjacent = vec![Vec::new(); n];
+    for (i, &(u, v, _)) in edges.iter().enumerate() {
+        adjacent[u].push((v, i));
+        adjacent[v].push((u, i));
+    }
+    let mut done = vec![false; n];
+    // the cheapest edge found into each vertex
+    let mut best: Vec<Option<(C, usize)>> = vec![None; n];
+    let mut heap = IndexedLazyHollowHeap::with_vertices(n);
+    let mut chosen = Vec::new();
+    for r in 0..n {
+        if done[r] {
+            continue;
+        }
+        done[r] = true;
+        let mut u = r;
+        loop {
+            for &(v, e) in &adjacent[u] {
+                let key = (edges[e].2, e);
+                if done[v] {
+                    continue;
+                }
+                match best[v] {
+                    Some(b) if b <= key => {}
+                    _ => {
+                        best[v] = Some(key);
+                        heap.push(v, key);
+                    }
+                }
+            }
+            match heap.pop() {
+                Some(((_, e), v)) => {
+                    done[v] = true;
+                    chosen.push(e);
+                    u = v;
+                }
+                None => break,
+            }
+        }
+    }
+    make_ret(edges, chosen)
+}
+
+/// Borůvka's algorithm in `O(m log n)` time, which joins every component to its cheapest
+/// neighbor in each round.
+pub fn boruvka<C: Cost>(n: usize, edges: &[(usize, usize, C)]) -> Ret<C> {
+    let mut uf = UnionFind::new(n);
+    let mut chosen = Vec::new();
+    loop {
+        let mut cheapest: Vec<Option<(C, usize)>> = vec![None; n];
+        for (e, &(u, v, c)) in edges.iter().enumerate() {
+            let (ru, rv) = (uf.find_mut(u), uf.find_mut(v));
+            if ru == rv {
+                continue;
+            }
+            for &r in &[ru, rv] {
+                match cheapest[r] {
+                    Some(b) if b <= (c, e) => {}
+                    _ => cheapest[r] = Some((c, e)),
+                }
+            }
+        }
+        let mut joined = false;
+        for (_, e) in cheapest.into_iter().flatten() {
+            if uf.unite(edges[e].0, edges[e].1).0 {
+                chosen.push(e);
+                joined = true;
+            }
+        }
+        if !joined {
+            break;
+        }
+    }
+    make_ret(edges, chosen)
+}
+
+/// Maximum edges on the paths of a minimum spanning forest by binary lifting, in `O(n log n)`
+/// time to build and `O(log n)` time per query, for exchanging an edge of the forest.
+pub struct PathMax<C> {
+    edges: Vec<(usize, usize, C)>,
+    cost: C,
+    tree: Vec<bool>,
+    depth: Vec<usize>,
+    // the 2^k-th ancestor, and the max edge on the way or !0
+    up: Vec<Vec<usize>>,
+    max: Vec<Vec<usize>>,
+}
+
+impl<C: Cost> PathMax<C> {
+    pub fn new(n: usize, edges: &[(usize, usize, C)], forest: &Ret<C>) -> Self {
+        let mut adjacent = vec![Vec::new(); n];
+        let mut tree = vec![false; edges.len()];
+        for &e in &forest.edges {
+            let (u, v, _) = edges[e];
+            adjacent[u].push((v, e));
+            adjacent[v].push((u, e));
+            tree[e] = true;
+        }
+        let mut depth = vec![!0; n];
+        let mut parent = vec![(0, !0); n];
+        let mut stack = Vec::new();
+        for r in 0..n {
+            if depth[r] != !0 {
+                continue;
+            }
+            depth[r] = 0;
+            parent[r] = (r, !0);
+            stack.push(r);
+            while let Some(u) = stack.pop() {
+                for &(v, e) in &adjacent[u] {
+                    if depth[v] == !0 {
+                        depth[v] = depth[u] + 1;
+                        parent[v] = (u, e);
+                        stack.push(v);
+                    }
+                }
+            }
+        }
+        let mut ret = Self {
+            edges: edges.to_vec(),
+            cost: forest.cost,
+            tree,
+            depth,
+            up: vec![parent.iter().map(|&(p, _)| p).collect()],
+            max: vec![parent.iter().map(|&(_, e)| e).collect()],
+        };
+        let mut k = 0;
+        while 1 << (k + 1) < n {
+            let (up, max) = (&ret.up[k], &ret.max[k]);
+            let next_up = (0..n).map(|v| up[up[v]]).collect();
+            let next_max = (0..n).map(|v| ret.larger(max[v], max[up[v]])).collect();
+            ret.up.push(next_up);
+            ret.max.push(next_max);
+            k += 1;
+        }
+        ret
+    }
+
+    fn larger(&self, a: usize, b: usize) -> usize {
+        if a == !0 {
+            b
+        } else if b == !0 || (self.edges[a].2, a) > (self.edges[b].2, b) {
+            a
+        } else {
+            b
+        }
+    }
+
+    /// The most expensive edge on the path between `u` and `v` in the forest, or `None` if
+    /// `u == v` or they are not connected.
+    pub fn get(&self, mut u: usize, mut v: usize) -> Option<usize> {
+        if self.depth[u] < self.depth[v] {
+            swap(&mut u, &mut v);
+        }
+        let mut ret = !0;
+        let diff = self.depth[u] - self.depth[v];
+        for k in 0..self.up.len() {
+            if diff >> k & 1 == 1 {
+                ret = self.larger(ret, self.max[k][u]);
+                u = self.up[k][u];
+            }
+        }
+        if u != v {
+            for k in (0..self.up.len()).rev() {
+                if self.up[k][u] != self.up[k][v] {
+                    ret = self.larger(ret, self.larger(self.max[k][u], self.max[k][v]));
+                    u = self.up[k][u];
+                    v = self.up[k][v];
+                }
+            }
+            if self.up[0][u] != self.up[0][v] {
+                return None;
+            }
+            ret = self.larger(ret, self.larger(self.max[0][u], self.max[0][v]));
+        }
+        if ret == !0 {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+
+    /// The cost of the minimum spanning forest containing the edge `e`, together with the
+    /// edge of the forest it replaces if any. `None` for a self-loop.
+    pub fn with_forced_edge(&self, e: usize) -> Option<(C, Option<usize>)> {
+        let (u, v, c) = self.edges[e];
+        if u == v {
+            return None;
+        }
+        if self.tree[e] {
+            return Some((self.cost, None));
+        }
+        let f = self.get(u, v).unwrap();
+        Some((self.cost - self.edges[f].2 + c, Some(f)))
+    }
+
+    /// The cheapest spanning forest other than the minimum one as its cost, the removed edge and
+    /// the added edge, or `None` if there is no other one.
+    pub fn second_best(&self) -> Option<(C, usize, usize)> {
+        let mut ret: Option<(C, usize, usize)> = None;
+        for e in 0..self.edges.len() {
+            if let Some((cost, Some(f))) = self.with_forced_edge(e) {
+                match ret {
+                    Some((best, _, _)) if best <= cost => {}
+                    _ => ret = Some((cost, f, e)),
+                }
+            }
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let edges = vec![
+            (0, 1, 4),
+            (1, 2, 2),
+            (0, 2, 3),
+            (2, 3, 5),
+            (3, 3, 0),
+            (1, 3, 5),
+            (4, 5, 1),
+            (1, 2, 2),
+        ];
+        for ret in [kruskal(6, &edges), prim(6, &edges), boruvka(6, &edges)] {
+            assert_eq!(ret.get_cost(), 11);
+            assert_eq!(ret.get_edges(), &[1, 2, 3, 6]);
+        }
+        let pm = PathMax::new(6, &edges, &kruskal(6, &edges));
+        assert_eq!(pm.get(0, 3), Some(3));
+        assert_eq!(pm.get(0, 1), Some(2));
+        assert_eq!(pm.get(0, 4), None);
+        assert_eq!(pm.with_forced_edge(0), Some((12, Some(2))));
+        assert_eq!(pm.with_forced_edge(3), Some((11, None)));
+        assert_eq!(pm.with_forced_edge(4), None);
+        assert_eq!(pm.second_best(), Some((11, 3, 5)));
+    }
+}
@@ -0,0 +1,162 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::data_structures::{rollback_union_find::RollbackUnionFind, weighted_union_find::Group};
+
+/// Connectivity over a timeline of edge insertions and deletions known in advance, by a segment
+/// tree over the time and a DFS on it with [`RollbackUnionFind`], in `O(m log t log n)` time for
+/// `m` edges and `t` points of time.
+pub struct OfflineDynamicConnectivity {
+    n: usize,
+    num_times: usize,
+    // endpoints and the interval of time
+    edges: Vec<(usize, usize, usize, usize)>,
+    // the times the copies of each edge were inserted at
+    alive: HashMap<(usize, usize), Vec<usize>>,
+}
+
+fn key(u: usize, v: usize) -> (usize, usize) {
+    match u.cmp(&v) {
+        Ordering::Greater => (v, u),
+        _ => (u, v),
+    }
+}
+
+impl OfflineDynamicConnectivity {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            num_times: 0,
+            edges: Vec::new(),
+            alive: HashMap::new(),
+        }
+    }
+
+    /// Inserts an undirected edge, which may be parallel to others.
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(
+            u < self.n && v < self.n,
+            "Vertex ({}, {}) out of range {}",
+            u,
+            v,
+            self.n
+        );
+        self.alive
+            .entry(key(u, v))
+            .or_default()
+            .push(self.num_times);
+    }
+
+    /// Deletes one copy of an edge, which should exist.
+    pub fn remove_edge(&mut self, u: usize, v: usize) {
+        let (u, v) = key(u, v);
+        let start = self
+            .alive
+            .get_mut(&(u, v))
+            .and_then(Vec::pop)
+            .unwrap_or_else(|| panic!("Edge ({}, {}) does not exist", u, v));
+        if start < self.num_times {
+            self.edges.push((u, v, start, self.num_times));
+        }
+    }
+
+    /// Adds a point of time with the current edges, returning its index.
+    pub fn query(&mut self) -> usize {
+        self.num_times += 1;
+        self.num_times - 1
+    }
+
+    /// Calls `f` with each point of time in order and `uf` holding the edges at that time.
+    /// The callback may change the values of `uf` but should undo its unions.
+    pub fn run<G, F>(&self, uf: &mut RollbackUnionFind<G>, mut f: F)
+    where
+        G: Group,
+        F: FnMut(usize, &mut RollbackUnionFind<G>),
+    {
+        let t = self.num_times;
+        if t == 0 {
+            return;
+        }
+        let size = t.next_power_of_two();
+        let mut segments = vec![Vec::new(); 2 * size];
+        let remaining = self
+            .alive
+            .iter()
+            .flat_map(|(&(u, v), starts)| starts.iter().map(move |&start| (u, v, start, t)));
+        for (u, v, start, end) in self.edges.iter().copied().chain(remaining) {
+            let (mut l, mut r) = (start + size, end + size);
+            while l < r {
+                if l & 1 == 1 {
+                    segments[l].push((u, v));
+                    l += 1;
+                }
+                if r & 1 == 1 {
+                    r -= 1;
+                    segments[r].push((u, v));
+                }
+                l >>= 1;
+                r >>= 1;
+            }
+        }
+        Self::dfs(&segments, 1, 0, size, t, uf, &mut f);
+    }
+
+    fn dfs<G, F>(
+        segments: &[Vec<(usize, usize)>],
+        x: usize,
+        begin: usize,
+        width: usize,
+        t: usize,
+        uf: &mut RollbackUnionFind<G>,
+        f: &mut F,
+    ) where
+        G: Group,
+        F: FnMut(usize, &mut RollbackUnionFind<G>),
+    {
+        if begin >= t {
+            return;
+        }
+        let snapshot = uf.snapshot();
+        for &(u, v) in &segments[x] {
+            uf.unite(u, v);
+        }
+        if width == 1 {
+            f(begin, uf);
+        } else {
+            let half = width / 2;
+            Self::dfs(segments, 2 * x, begin, half, t, uf, f);
+            Self::dfs(segments, 2 * x + 1, begin + half, half, t, uf, f);
+        }
+        uf.rollback(snapshot);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut dc = OfflineDynamicConnectivity::new(4);
+        let mut expected = Vec::new();
+        dc.add_edge(0, 1);
+        dc.add_edge(1, 0);
+        expected.push((dc.query(), 3, true));
+        dc.remove_edge(0, 1);
+        dc.add_edge(2, 3);
+        expected.push((dc.query(), 2, true));
+        dc.remove_edge(0, 1);
+        dc.add_edge(3, 3);
+        expected.push((dc.query(), 3, false));
+        dc.add_edge(1, 2);
+        dc.add_edge(0, 0);
+        dc.remove_edge(0, 0);
+        expected.push((dc.query(), 2, false));
+        let mut uf = RollbackUnionFind::<()>::new(4);
+        let mut answers = Vec::new();
+        dc.run(&mut uf, |t, uf| {
+            answers.push((t, uf.num_components(), uf.same(0, 1)))
+        });
+        assert_eq!(answers, expected);
+        assert_eq!(uf.num_components(), 4);
+    }
+}
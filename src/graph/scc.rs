@@ -0,0 +1,189 @@
+use std::cmp::min;
+
+use crate::graph::Graph;
+
+/// Strongly connected components numbered in a topological order, i.e. every edge goes from a
+/// component to the same or a later one.
+pub struct Ret {
+    component: Vec<usize>,
+    num_components: usize,
+}
+impl Ret {
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+    pub fn get_component(&self, v: usize) -> usize {
+        self.component[v]
+    }
+    /// Vertices of each component in the topological order.
+    pub fn get_components(&self) -> Vec<Vec<usize>> {
+        let mut components = vec![Vec::new(); self.num_components];
+        for (v, &c) in self.component.iter().enumerate() {
+            components[c].push(v);
+        }
+        components
+    }
+    /// Edges between the components without duplicates, where each list is sorted.
+    pub fn condensation<G: Graph + ?Sized>(&self, g: &G) -> Vec<Vec<usize>> {
+        let mut dag = vec![Vec::new(); self.num_components];
+        for u in 0..g.num_vertices() {
+            for (_, v, _) in g.out_edges(u) {
+                let (cu, cv) = (self.component[u], self.component[v]);
+                if cu != cv {
+                    dag[cu].push(cv);
+                }
+            }
+        }
+        for adj in dag.iter_mut() {
+            adj.sort_unstable();
+            adj.dedup();
+        }
+        dag
+    }
+}
+
+/// Tarjan's algorithm in `O(n + m)` time without recursion.
+pub fn tarjan<G: Graph + ?Sized>(g: &G) -> Ret {
+    let n = g.num_vertices();
+    let mut index = vec![!0; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    // vertex and the next out-edge to visit
+    let mut calls: Vec<(usize, usize)> = Vec::new();
+    let mut counter = 0;
+    let mut component = vec![!0; n];
+    let mut num_components = 0;
+    for r in 0..n {
+        if index[r] != !0 {
+            continue;
+        }
+        index[r] = counter;
+        low[r] = counter;
+        counter += 1;
+        stack.push(r);
+        on_stack[r] = true;
+        calls.push((r, 0));
+        while let Some((v, i)) = calls.last_mut() {
+            let v = *v;
+            if *i < g.out_degree(v) {
+                let (_, w, _) = g.out_edge(v, *i);
+                *i += 1;
+                if index[w] == !0 {
+                    index[w] = counter;
+                    low[w] = counter;
+                    counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    calls.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = min(low[v], index[w]);
+                }
+                continue;
+            }
+            calls.pop();
+            if let Some(&(p, _)) = calls.last() {
+                low[p] = min(low[p], low[v]);
+            }
+            if low[v] == index[v] {
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    component[w] = num_components;
+                    if w == v {
+                        break;
+                    }
+                }
+                num_components += 1;
+            }
+        }
+    }
+    // components are found in the reverse topological order
+    for c in component.iter_mut() {
+        *c = num_components - 1 - *c;
+    }
+    Ret {
+        component,
+        num_components,
+    }
+}
+
+/// Kosaraju's algorithm in `O(n + m)` time, which is a DFS on `g` and another on the reverse.
+pub fn kosaraju<G: Graph + ?Sized>(g: &G) -> Ret {
+    let n = g.num_vertices();
+    let mut reverse = vec![Vec::new(); n];
+    let mut visited = vec![false; n];
+    let mut post_order = Vec::with_capacity(n);
+    let mut calls: Vec<(usize, usize)> = Vec::new();
+    for r in 0..n {
+        if visited[r] {
+            continue;
+        }
+        visited[r] = true;
+        calls.push((r, 0));
+        while let Some((v, i)) = calls.last_mut() {
+            let v = *v;
+            if *i < g.out_degree(v) {
+                let (_, w, _) = g.out_edge(v, *i);
+                *i += 1;
+                reverse[w].push(v);
+                if !visited[w] {
+                    visited[w] = true;
+                    calls.push((w, 0));
+                }
+            } else {
+                calls.pop();
+                post_order.push(v);
+            }
+        }
+    }
+    let mut component = vec![!0; n];
+    let mut num_components = 0;
+    let mut stack = Vec::new();
+    for &r in post_order.iter().rev() {
+        if component[r] != !0 {
+            continue;
+        }
+        component[r] = num_components;
+        stack.push(r);
+        while let Some(v) = stack.pop() {
+            for &w in &reverse[v] {
+                if component[w] == !0 {
+                    component[w] = num_components;
+                    stack.push(w);
+                }
+            }
+        }
+        num_components += 1;
+    }
+    Ret {
+        component,
+        num_components,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut g = vec![Vec::new(); 6];
+        for &(u, v) in &[(1, 4), (5, 2), (3, 0), (5, 5), (4, 1), (0, 3), (4, 2)] {
+            g[u].push((v, ()));
+        }
+        for ret in [tarjan(&g), kosaraju(&g)] {
+            assert_eq!(ret.num_components(), 4);
+            let components = ret.get_components();
+            let position = |v: usize| components.iter().position(|c| c.contains(&v)).unwrap();
+            assert!(position(1) < position(2));
+            assert!(position(5) < position(2));
+            let mut sorted = components.clone();
+            sorted.sort();
+            assert_eq!(sorted, vec![vec![0, 3], vec![1, 4], vec![2], vec![5]]);
+            let dag = ret.condensation(&g);
+            assert_eq!(dag.iter().map(Vec::len).sum::<usize>(), 2);
+            assert_eq!(dag[ret.get_component(4)], vec![ret.get_component(2)]);
+        }
+    }
+}
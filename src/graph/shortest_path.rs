@@ -0,0 +1,376 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use crate::{
+    data_structures::lazy_hollow_heap::{LazyHollowHeap, Ref},
+    flows::Cost,
+    graph::{EdgeRef, Graph},
+};
+
+/// Priority queue of vertices for [`dijkstra_with_heap`].
+pub trait VertexHeap<K> {
+    fn with_vertices(n: usize) -> Self;
+    /// Inserts `v`, or decreases its key if it is in the heap. May leave a stale entry, which
+    /// [`VertexHeap::pop`] returns later.
+    fn push(&mut self, v: usize, key: K);
+    fn pop(&mut self) -> Option<(K, usize)>;
+}
+
+/// [`LazyHollowHeap`] with a reference to each vertex, so that keys are decreased in place.
+pub struct IndexedLazyHollowHeap<K> {
+    heap: LazyHollowHeap<K, usize>,
+    refs: Vec<Option<Ref>>,
+}
+
+impl<K: Ord + Clone> VertexHeap<K> for IndexedLazyHollowHeap<K> {
+    fn with_vertices(n: usize) -> Self {
+        Self {
+            heap: LazyHollowHeap::new(),
+            refs: (0..n).map(|_| None).collect(),
+        }
+    }
+    fn push(&mut self, v: usize, key: K) {
+        match self.refs[v].as_mut() {
+            Some(r) => self.heap.update_key_better(r, key),
+            None => self.refs[v] = Some(self.heap.insert(key, v)),
+        }
+    }
+    fn pop(&mut self) -> Option<(K, usize)> {
+        let v = *self.heap.find_min()?.1;
+        Some(self.heap.delete(self.refs[v].take().unwrap()))
+    }
+}
+
+impl<K: Ord> VertexHeap<K> for BinaryHeap<Reverse<(K, usize)>> {
+    fn with_vertices(n: usize) -> Self {
+        BinaryHeap::with_capacity(n)
+    }
+    fn push(&mut self, v: usize, key: K) {
+        BinaryHeap::push(self, Reverse((key, v)));
+    }
+    fn pop(&mut self) -> Option<(K, usize)> {
+        BinaryHeap::pop(self).map(|Reverse(entry)| entry)
+    }
+}
+
+/// Distances from the source and the last edges of shortest paths.
+pub struct Ret<C, E = EdgeRef> {
+    dist: Vec<Option<C>>,
+    // the previous vertex and the edge from there
+    parent: Vec<Option<(usize, E)>>,
+}
+impl<C: Cost, E: Copy> Ret<C, E> {
+    fn new(n: usize, s: usize) -> Self {
+        let mut dist = vec![None; n];
+        dist[s] = Some(C::zero());
+        Self {
+            dist,
+            parent: vec![None; n],
+        }
+    }
+
+    /// Sets the distance to `v` if `d` is shorter.
+    fn relax(&mut self, v: usize, d: C, e: (usize, E)) -> bool {
+        match self.dist[v] {
+            Some(dv) if dv <= d => false,
+            _ => {
+                self.dist[v] = Some(d);
+                self.parent[v] = Some(e);
+                true
+            }
+        }
+    }
+
+    /// A cycle of parent edges reached from `v` by following the parents, if any.
+    fn find_cycle(&self, mut v: usize) -> Option<Vec<E>> {
+        for _ in 0..self.parent.len() {
+            v = self.parent[v]?.0;
+        }
+        let start = v;
+        let mut cycle = Vec::new();
+        loop {
+            let (u, e) = self.parent[v].unwrap();
+            cycle.push(e);
+            v = u;
+            if v == start {
+                break;
+            }
+        }
+        cycle.reverse();
+        Some(cycle)
+    }
+
+    pub fn get_dist(&self, v: usize) -> Option<C> {
+        self.dist[v]
+    }
+    pub fn get_dists(&self) -> &[Option<C>] {
+        &self.dist
+    }
+    /// The last edge of the shortest path to `v`.
+    pub fn get_parent(&self, v: usize) -> Option<E> {
+        self.parent[v].map(|(_, e)| e)
+    }
+    pub fn get_parent_vertex(&self, v: usize) -> Option<usize> {
+        self.parent[v].map(|(u, _)| u)
+    }
+    /// Edges of a shortest path to `t` in order, or `None` if `t` is unreachable.
+    pub fn get_path(&self, t: usize) -> Option<Vec<E>> {
+        self.dist[t]?;
+        let mut path = Vec::new();
+        let mut v = t;
+        while let Some((u, e)) = self.parent[v] {
+            path.push(e);
+            v = u;
+        }
+        path.reverse();
+        Some(path)
+    }
+    /// Vertices of a shortest path from the source to `t`, or `None` if `t` is unreachable.
+    pub fn get_path_vertices(&self, t: usize) -> Option<Vec<usize>> {
+        self.dist[t]?;
+        let mut path = vec![t];
+        let mut v = t;
+        while let Some((u, _)) = self.parent[v] {
+            path.push(u);
+            v = u;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Dijkstra's algorithm on [`LazyHollowHeap`] in `O(m + n log n)` time. Costs should be
+/// non-negative.
+pub fn dijkstra<G, C>(g: &G, s: usize) -> Ret<C, G::EdgeId>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    dijkstra_with_heap::<G, C, IndexedLazyHollowHeap<C>>(g, s)
+}
+
+pub fn dijkstra_with_heap<G, C, H>(g: &G, s: usize) -> Ret<C, G::EdgeId>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+    H: VertexHeap<C>,
+{
+    let n = g.num_vertices();
+    let mut ret = Ret::new(n, s);
+    let mut done = vec![false; n];
+    let mut heap = H::with_vertices(n);
+    heap.push(s, C::zero());
+    while let Some((d, u)) = heap.pop() {
+        if done[u] {
+            continue;
+        }
+        done[u] = true;
+        for (e, v, &c) in g.out_edges(u) {
+            assert!(!c.is_negative(), "cost {} should be non-negative", c);
+            if !done[v] && ret.relax(v, d + c, (u, e)) {
+                heap.push(v, d + c);
+            }
+        }
+    }
+    ret
+}
+
+/// Bellman-Ford algorithm in `O(nm)` time. Returns a negative cycle reachable from `s` as its
+/// edges in order if any.
+pub fn bellman_ford<G, C>(g: &G, s: usize) -> Result<Ret<C, G::EdgeId>, Vec<G::EdgeId>>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let n = g.num_vertices();
+    let mut ret = Ret::new(n, s);
+    let mut updated = None;
+    for _ in 0..n {
+        updated = None;
+        for u in 0..n {
+            let du = match ret.dist[u] {
+                Some(du) => du,
+                None => continue,
+            };
+            for (e, v, &c) in g.out_edges(u) {
+                if ret.relax(v, du + c, (u, e)) {
+                    updated = Some(v);
+                }
+            }
+        }
+        if updated.is_none() {
+            return Ok(ret);
+        }
+    }
+    // the vertex updated in the n-th round is reached from a cycle of the parent edges
+    Err(ret.find_cycle(updated.unwrap()).unwrap())
+}
+
+/// Shortest path faster algorithm, i.e. Bellman-Ford with a queue of updated vertices, which is
+/// usually much faster though the worst case is the same. Returns a negative cycle reachable from
+/// `s` as its edges in order if any.
+pub fn spfa<G, C>(g: &G, s: usize) -> Result<Ret<C, G::EdgeId>, Vec<G::EdgeId>>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let n = g.num_vertices();
+    let mut ret = Ret::new(n, s);
+    let mut queue = VecDeque::new();
+    let mut in_queue = vec![false; n];
+    // number of edges on the path found to the vertex
+    let mut len = vec![0; n];
+    queue.push_back(s);
+    in_queue[s] = true;
+    while let Some(u) = queue.pop_front() {
+        in_queue[u] = false;
+        let du = ret.dist[u].unwrap();
+        for (e, v, &c) in g.out_edges(u) {
+            if !ret.relax(v, du + c, (u, e)) {
+                continue;
+            }
+            len[v] = len[u] + 1;
+            if len[v] >= n {
+                // a walk of n edges, though the parent edges may have changed along it
+                if let Some(cycle) = ret.find_cycle(v) {
+                    return Err(cycle);
+                }
+            }
+            if !in_queue[v] {
+                in_queue[v] = true;
+                queue.push_back(v);
+            }
+        }
+    }
+    Ok(ret)
+}
+
+/// Breadth first search with a deque for costs of zero or one in `O(n + m)` time.
+pub fn zero_one_bfs<G, C>(g: &G, s: usize) -> Ret<C, G::EdgeId>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let n = g.num_vertices();
+    let mut ret = Ret::new(n, s);
+    let mut done = vec![false; n];
+    let mut deque = VecDeque::new();
+    deque.push_back((C::zero(), s));
+    while let Some((d, u)) = deque.pop_front() {
+        if done[u] {
+            continue;
+        }
+        done[u] = true;
+        for (e, v, &c) in g.out_edges(u) {
+            if done[v] || !ret.relax(v, d + c, (u, e)) {
+                continue;
+            }
+            if c.is_zero() {
+                deque.push_front((d, v));
+            } else {
+                assert!(c == C::one(), "cost {} should be zero or one", c);
+                deque.push_back((d + c, v));
+            }
+        }
+    }
+    ret
+}
+
+/// Relaxes the edges in a topological order in `O(n + m)` time, allowing negative costs.
+/// Returns `None` if the graph has a cycle.
+pub fn dag_shortest_path<G, C>(g: &G, s: usize) -> Option<Ret<C, G::EdgeId>>
+where
+    G: Graph<Weight = C> + ?Sized,
+    C: Cost,
+{
+    let n = g.num_vertices();
+    let mut in_degree = vec![0; n];
+    for u in 0..n {
+        for (_, v, _) in g.out_edges(u) {
+            in_degree[v] += 1;
+        }
+    }
+    let mut order: Vec<_> = (0..n).filter(|&v| in_degree[v] == 0).collect();
+    let mut head = 0;
+    while head < order.len() {
+        let u = order[head];
+        head += 1;
+        for (_, v, _) in g.out_edges(u) {
+            in_degree[v] -= 1;
+            if in_degree[v] == 0 {
+                order.push(v);
+            }
+        }
+    }
+    if order.len() < n {
+        return None;
+    }
+    let mut ret = Ret::new(n, s);
+    for u in order {
+        if let Some(du) = ret.dist[u] {
+            for (e, v, &c) in g.out_edges(u) {
+                ret.relax(v, du + c, (u, e));
+            }
+        }
+    }
+    Some(ret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut g = vec![Vec::new(); 5];
+        for &(u, v, c) in &[
+            (0, 1, 1),
+            (0, 2, 4),
+            (1, 2, 2),
+            (2, 3, 1),
+            (1, 3, 5),
+            (4, 0, 1),
+        ] {
+            g[u].push((v, c));
+        }
+        let expected = [Some(0), Some(1), Some(3), Some(4), None];
+        let ret = dijkstra(&g, 0);
+        assert_eq!(ret.get_dists(), &expected[..]);
+        assert_eq!(ret.get_path_vertices(3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(ret.get_path(3), Some(vec![(0, 0), (1, 0), (2, 0)]));
+        assert_eq!(ret.get_path(4), None);
+        let ret = dijkstra_with_heap::<_, _, BinaryHeap<_>>(&g, 0);
+        assert_eq!(ret.get_dists(), &expected[..]);
+        assert_eq!(bellman_ford(&g, 0).unwrap().get_dists(), &expected[..]);
+        assert_eq!(spfa(&g, 0).unwrap().get_dists(), &expected[..]);
+        assert_eq!(dag_shortest_path(&g, 0).unwrap().get_dists(), &expected[..]);
+
+        let unit: Vec<Vec<_>> = g
+            .iter()
+            .map(|adj| adj.iter().map(|&(v, c)| (v, c % 2)).collect())
+            .collect();
+        let ret = zero_one_bfs(&unit, 0);
+        assert_eq!(
+            ret.get_dists(),
+            &[Some(0), Some(1), Some(0), Some(1), None][..]
+        );
+
+        // 1 -> 2 -> 3 -> 1 costs -1 in total
+        g[3].push((1, -4));
+        assert_eq!(dag_shortest_path(&g, 0).map(|ret| ret.dist), None);
+        let cycle = vec![(1, 0), (2, 0), (3, 0)];
+        let mut found = bellman_ford(&g, 0).err().unwrap();
+        let start = found.iter().position(|e| e.0 == 1).unwrap();
+        found.rotate_left(start);
+        assert_eq!(found, cycle);
+        let mut found = spfa(&g, 0).err().unwrap();
+        let start = found.iter().position(|e| e.0 == 1).unwrap();
+        found.rotate_left(start);
+        assert_eq!(found, cycle);
+        assert!(spfa(&g, 4).is_err());
+        assert!(bellman_ford(&g, 3).is_err());
+        assert!(bellman_ford(&vec![Vec::<(usize, i32)>::new(); 1], 0).is_ok());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::graph::scc::tarjan;
+
+/// 2-SAT on the implication graph, where the literal `x_i == f` is the vertex `2 * i + f`.
+pub struct TwoSat {
+    n: usize,
+    implications: Vec<Vec<(usize, ())>>,
+}
+
+fn literal(i: usize, f: bool) -> usize {
+    2 * i + f as usize
+}
+
+impl TwoSat {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            implications: vec![Vec::new(); 2 * n],
+        }
+    }
+
+    pub fn num_variables(&self) -> usize {
+        self.n
+    }
+
+    /// Adds a variable, e.g. for auxiliary ones.
+    pub fn new_variable(&mut self) -> usize {
+        self.implications.push(Vec::new());
+        self.implications.push(Vec::new());
+        self.n += 1;
+        self.n - 1
+    }
+
+    fn imply(&mut self, a: usize, b: usize) {
+        self.implications[a].push((b, ()));
+        self.implications[b ^ 1].push((a ^ 1, ()));
+    }
+
+    /// Adds a clause `(x_i == f) || (x_j == g)`.
+    pub fn add_clause(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        self.imply(literal(i, !f), literal(j, g));
+    }
+
+    /// Adds a clause `x_i == f`.
+    pub fn set(&mut self, i: usize, f: bool) {
+        self.add_clause(i, f, i, f);
+    }
+
+    /// Adds a clause `(x_i == f) => (x_j == g)`.
+    pub fn add_implication(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        self.imply(literal(i, f), literal(j, g));
+    }
+
+    /// Adds clauses meaning exactly one of `x_i == f` and `x_j == g` holds.
+    pub fn add_xor(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        self.add_clause(i, f, j, g);
+        self.add_clause(i, !f, j, !g);
+    }
+
+    /// Adds clauses meaning at most one of the literals holds, with `O(k)` clauses and
+    /// auxiliary variables by prefix encoding: `s_k` holds if any of the first `k + 1` literals
+    /// does.
+    pub fn add_at_most_one(&mut self, literals: &[(usize, bool)]) {
+        let mut prefix: Option<usize> = None;
+        for &(i, f) in literals {
+            let l = literal(i, f);
+            let s = literal(self.new_variable(), true);
+            self.imply(l, s);
+            if let Some(p) = prefix {
+                self.imply(p, s);
+                self.imply(p, l ^ 1);
+            }
+            prefix = Some(s);
+        }
+    }
+
+    /// A satisfying assignment of all the variables including auxiliary ones, or `None` if
+    /// unsatisfiable.
+    pub fn solve(&self) -> Option<Vec<bool>> {
+        let scc = tarjan(&self.implications);
+        (0..self.n)
+            .map(|i| {
+                let (f, t) = (
+                    scc.get_component(literal(i, false)),
+                    scc.get_component(literal(i, true)),
+                );
+                // a literal implied by its negation holds
+                if f == t {
+                    None
+                } else {
+                    Some(f < t)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut ts = TwoSat::new(4);
+        ts.add_clause(0, true, 1, true);
+        ts.add_implication(0, true, 2, false);
+        ts.add_xor(2, true, 3, true);
+        ts.set(3, false);
+        let x = ts.solve().unwrap();
+        assert!(x[0] || x[1]);
+        assert!(!x[0] || !x[2]);
+        assert!(x[2] != x[3] && !x[3]);
+        assert!(!x[0] && x[1]);
+
+        let mut ts = TwoSat::new(4);
+        ts.add_at_most_one(&[(0, true), (1, false), (2, true), (3, true)]);
+        ts.set(1, true);
+        ts.set(2, true);
+        let x = ts.solve().unwrap();
+        assert_eq!(&x[..4], &[false, true, true, false]);
+        ts.set(3, true);
+        assert_eq!(ts.solve(), None);
+    }
+}
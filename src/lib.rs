@@ -1,4 +1,5 @@
 pub mod a_plus_b;
 pub mod data_structures;
 pub mod flows;
+pub mod graph;
 pub mod utils;
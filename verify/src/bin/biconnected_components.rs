@@ -0,0 +1,24 @@
+use proconio::input;
+use ralgo::graph::lowlink::Lowlink;
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/biconnected_components
+
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        edges: [(usize, usize); m],
+    }
+    let ll = Lowlink::new(n, &edges);
+    let components = ll.biconnected_components().to_vec();
+    let mut out = String::new();
+    out.push_str(&format!("{}\n", components.len()));
+    for c in components {
+        out.push_str(&c.len().to_string());
+        for v in c {
+            out.push_str(&format!(" {}", v));
+        }
+        out.push('\n');
+    }
+    print!("{}", out);
+}
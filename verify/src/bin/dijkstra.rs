@@ -0,0 +1,28 @@
+use proconio::input;
+use ralgo::graph::shortest_path::dijkstra;
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/shortest_path
+
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        s: usize,
+        t: usize,
+        edges: [(usize, usize, i64); m],
+    }
+    let mut g = vec![Vec::new(); n];
+    for (u, v, c) in edges.into_iter() {
+        g[u].push((v, c));
+    }
+    let ret = dijkstra(&g, s);
+    if let Some(d) = ret.get_dist(t) {
+        let path = ret.get_path_vertices(t).unwrap();
+        println!("{} {}", d, path.len() - 1);
+        for w in path.windows(2) {
+            println!("{} {}", w[0], w[1]);
+        }
+    } else {
+        println!("-1");
+    }
+}
@@ -0,0 +1,21 @@
+use proconio::input;
+use ralgo::graph::directed_mst::directed_mst;
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/directedmst
+
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        s: usize,
+        edges: [(usize, usize, i64); m],
+    }
+    let ret = directed_mst(n, s, &edges).unwrap();
+    let parents: Vec<_> = (0..n)
+        .map(|v| match ret.get_parent(v) {
+            Some(e) => edges[e].0.to_string(),
+            None => v.to_string(),
+        })
+        .collect();
+    println!("{}\n{}", ret.get_cost(), parents.join(" "));
+}
@@ -0,0 +1,51 @@
+use ac_library::Monoid;
+use proconio::input;
+use ralgo::{data_structures::splay_tree::euler_tour_tree::EulerTourTree, utils::arena::Arena};
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/dynamic_tree_vertex_add_subtree_sum
+
+enum Sum {}
+impl Monoid for Sum {
+    type S = u64;
+    fn identity() -> u64 {
+        0
+    }
+    fn binary_operation(a: &u64, b: &u64) -> u64 {
+        a + b
+    }
+}
+
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+        a: [u64; n],
+        edges: [(usize, usize); n - 1],
+    }
+    let arena = Arena::new();
+    let mut ett = EulerTourTree::<Sum>::new(&arena, a);
+    for (u, v) in edges {
+        ett.link(u, v);
+    }
+    let mut out = String::new();
+    for _ in 0..q {
+        input! { t: u8 }
+        match t {
+            0 => {
+                input! { u: usize, v: usize, w: usize, x: usize }
+                ett.cut(u, v);
+                ett.link(w, x);
+            }
+            1 => {
+                input! { p: usize, x: u64 }
+                let value = *ett.get(p) + x;
+                ett.set(p, value);
+            }
+            _ => {
+                input! { v: usize, p: usize }
+                out.push_str(&format!("{}\n", ett.subtree_product(v, p)));
+            }
+        }
+    }
+    print!("{}", out);
+}
@@ -0,0 +1,55 @@
+use ac_library::{ModInt998244353 as Mint, Monoid};
+use proconio::input;
+use ralgo::{
+    data_structures::splay_tree::{link_cut_tree::LinkCutTree, Reversible},
+    utils::arena::Arena,
+};
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/dynamic_tree_vertex_set_path_composite
+
+// x -> a x + b, applied from left to right
+enum Affine {}
+impl Monoid for Affine {
+    type S = (Mint, Mint);
+    fn identity() -> Self::S {
+        (1.into(), 0.into())
+    }
+    fn binary_operation(f: &Self::S, g: &Self::S) -> Self::S {
+        (f.0 * g.0, f.1 * g.0 + g.1)
+    }
+}
+
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+        f: [(Mint, Mint); n],
+        edges: [(usize, usize); n - 1],
+    }
+    let arena = Arena::new();
+    let lct = LinkCutTree::<Reversible<Affine>>::new(&arena, f);
+    for (u, v) in edges {
+        lct.link(u, v);
+    }
+    let mut out = String::new();
+    for _ in 0..q {
+        input! { t: u8 }
+        match t {
+            0 => {
+                input! { u: usize, v: usize, w: usize, x: usize }
+                lct.cut(u, v);
+                lct.link(w, x);
+            }
+            1 => {
+                input! { p: usize, c: Mint, d: Mint }
+                lct.set(p, (c, d));
+            }
+            _ => {
+                input! { u: usize, v: usize, x: Mint }
+                let (a, b) = lct.path_product(u, v).0;
+                out.push_str(&format!("{}\n", a * x + b));
+            }
+        }
+    }
+    print!("{}", out);
+}
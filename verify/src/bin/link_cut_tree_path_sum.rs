@@ -0,0 +1,51 @@
+use ac_library::Monoid;
+use proconio::input;
+use ralgo::{data_structures::splay_tree::link_cut_tree::LinkCutTree, utils::arena::Arena};
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/dynamic_tree_vertex_add_path_sum
+
+enum Sum {}
+impl Monoid for Sum {
+    type S = u64;
+    fn identity() -> u64 {
+        0
+    }
+    fn binary_operation(a: &u64, b: &u64) -> u64 {
+        a + b
+    }
+}
+
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+        a: [u64; n],
+        edges: [(usize, usize); n - 1],
+    }
+    let arena = Arena::new();
+    let lct = LinkCutTree::<Sum>::new(&arena, a);
+    for (u, v) in edges {
+        lct.link(u, v);
+    }
+    let mut out = String::new();
+    for _ in 0..q {
+        input! { t: u8 }
+        match t {
+            0 => {
+                input! { u: usize, v: usize, w: usize, x: usize }
+                lct.cut(u, v);
+                lct.link(w, x);
+            }
+            1 => {
+                input! { p: usize, x: u64 }
+                let value = *lct.get(p) + x;
+                lct.set(p, value);
+            }
+            _ => {
+                input! { u: usize, v: usize }
+                out.push_str(&format!("{}\n", *lct.path_product(u, v)));
+            }
+        }
+    }
+    print!("{}", out);
+}
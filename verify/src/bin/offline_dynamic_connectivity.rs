@@ -0,0 +1,62 @@
+use proconio::input;
+use ralgo::{
+    data_structures::{rollback_union_find::RollbackUnionFind, weighted_union_find::Group},
+    graph::offline_dynamic_connectivity::OfflineDynamicConnectivity,
+};
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/dynamic_graph_vertex_add_component_sum
+
+struct Sum;
+impl Group for Sum {
+    type Value = i64;
+    fn identity() -> i64 {
+        0
+    }
+    fn binary_operation(lhs: &i64, rhs: &i64) -> i64 {
+        lhs + rhs
+    }
+    fn inverse(val: &i64) -> i64 {
+        -val
+    }
+}
+
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+        a: [i64; n],
+    }
+    let mut dc = OfflineDynamicConnectivity::new(n);
+    // vertex and the value to add, or None for a sum
+    let mut queries = Vec::new();
+    for _ in 0..q {
+        input! { t: usize }
+        match t {
+            0 => {
+                input! { u: usize, v: usize }
+                dc.add_edge(u, v);
+            }
+            1 => {
+                input! { u: usize, v: usize }
+                dc.remove_edge(u, v);
+            }
+            2 => {
+                input! { v: usize, x: i64 }
+                dc.query();
+                queries.push((v, Some(x)));
+            }
+            _ => {
+                input! { v: usize }
+                dc.query();
+                queries.push((v, None));
+            }
+        }
+    }
+    let mut uf = RollbackUnionFind::<Sum>::with_values(a);
+    let mut out = String::new();
+    dc.run(&mut uf, |t, uf| match queries[t] {
+        (v, Some(x)) => uf.add(v, &x),
+        (v, None) => out.push_str(&format!("{}\n", uf.component_sum(v))),
+    });
+    print!("{}", out);
+}
@@ -0,0 +1,26 @@
+use proconio::input;
+use ralgo::data_structures::persistent_union_find::PersistentUnionFind;
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/persistent_unionfind
+
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+        queries: [(usize, i64, usize, usize); q],
+    }
+    let mut uf = PersistentUnionFind::new(n);
+    // the version of each graph, shifted by one for the initial graph
+    let mut versions = vec![uf.initial()];
+    let mut out = String::new();
+    for (t, k, u, v) in queries {
+        let version = versions[(k + 1) as usize];
+        if t == 0 {
+            versions.push(uf.unite(version, u, v).0);
+        } else {
+            out.push_str(if uf.same(version, u, v) { "1\n" } else { "0\n" });
+            versions.push(version);
+        }
+    }
+    print!("{}", out);
+}
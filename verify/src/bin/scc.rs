@@ -0,0 +1,27 @@
+use proconio::input;
+use ralgo::graph::scc::tarjan;
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/scc
+
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        edges: [(usize, usize); m],
+    }
+    let mut g = vec![Vec::new(); n];
+    for (u, v) in edges.into_iter() {
+        g[u].push((v, ()));
+    }
+    let components = tarjan(&g).get_components();
+    let mut out = String::new();
+    out.push_str(&format!("{}\n", components.len()));
+    for c in components {
+        out.push_str(&c.len().to_string());
+        for v in c {
+            out.push_str(&format!(" {}", v));
+        }
+        out.push('\n');
+    }
+    print!("{}", out);
+}
@@ -0,0 +1,68 @@
+use ac_library::Monoid;
+use proconio::input;
+use ralgo::{data_structures::splay_tree::Trees, utils::arena::Arena};
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/ordered_set
+
+enum Nop {}
+impl Monoid for Nop {
+    type S = ();
+    fn identity() {}
+    fn binary_operation(_: &(), _: &()) {}
+}
+
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+        a: [u32; n],
+    }
+    let arena = Arena::new();
+    let trees = Trees::<u32, Nop>::new(&arena);
+    let mut set = trees.empty_tree();
+    for x in a {
+        set.insert_by_key(trees.singleton_tree(x, ()));
+    }
+    let mut out = String::new();
+    for _ in 0..q {
+        input! { t: u8, x: u32 }
+        let ans = match t {
+            0 => {
+                if set.count(&x) == 0 {
+                    set.insert_by_key(trees.singleton_tree(x, ()));
+                }
+                continue;
+            }
+            1 => {
+                set.remove_by_key(&x);
+                continue;
+            }
+            2 => {
+                let k = x as usize;
+                if k <= set.len() {
+                    Some(*set.nth(k - 1).0)
+                } else {
+                    None
+                }
+            }
+            3 => {
+                out.push_str(&format!("{}\n", set.rank(&x) + set.count(&x)));
+                continue;
+            }
+            4 => {
+                let c = set.rank(&x) + set.count(&x);
+                if c == 0 {
+                    None
+                } else {
+                    Some(*set.nth(c - 1).0)
+                }
+            }
+            _ => set.lower_bound(&x).map(|(k, _)| *k),
+        };
+        match ans {
+            Some(k) => out.push_str(&format!("{}\n", k)),
+            None => out.push_str("-1\n"),
+        }
+    }
+    print!("{}", out);
+}
@@ -0,0 +1,79 @@
+use ac_library::{ModInt998244353 as Mint, Monoid};
+use proconio::input;
+use ralgo::{
+    data_structures::splay_tree::{Lazy, SeqActs, SeqHandle, Sequences},
+    utils::arena::Arena,
+};
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/dynamic_sequence_range_affine_range_sum
+
+// sums with the lengths
+enum Sum {}
+impl Monoid for Sum {
+    type S = (Mint, Mint);
+    fn identity() -> Self::S {
+        (0.into(), 0.into())
+    }
+    fn binary_operation(a: &Self::S, b: &Self::S) -> Self::S {
+        (a.0 + b.0, a.1 + b.1)
+    }
+}
+
+// x -> b x + c
+impl SeqActs for Sum {
+    type Map = (Mint, Mint);
+    fn identity_map() -> Self::Map {
+        (1.into(), 0.into())
+    }
+    fn composition(&(b, c): &Self::Map, &(d, e): &Self::Map) -> Self::Map {
+        (b * d, b * e + c)
+    }
+    fn map_value(&(b, c): &Self::Map, val: &mut (Mint, Mint)) {
+        val.0 = b * val.0 + c * val.1;
+    }
+    fn map_acc(f: &Self::Map, acc: &mut (Mint, Mint)) {
+        Self::map_value(f, acc);
+    }
+}
+
+fn main() {
+    input! {
+        n: usize,
+        q: usize,
+        a: [Mint; n],
+    }
+    let arena = Arena::new();
+    let seqs = Sequences::<Lazy<Sum>>::new(&arena);
+    let mut seq = seqs.empty_sequence();
+    for x in a {
+        seq = SeqHandle::concat(&seq, &seqs.singleton_sequence((x, 1.into())));
+    }
+    let mut out = String::new();
+    for _ in 0..q {
+        input! { t: u8 }
+        match t {
+            0 => {
+                input! { i: usize, x: Mint }
+                seq.insert(i, seqs.singleton_sequence((x, 1.into())));
+            }
+            1 => {
+                input! { i: usize }
+                seq.remove(i);
+            }
+            2 => {
+                input! { l: usize, r: usize }
+                seq.reverse_range(l..r);
+            }
+            3 => {
+                input! { l: usize, r: usize, b: Mint, c: Mint }
+                seq.apply(l..r, (b, c));
+            }
+            _ => {
+                input! { l: usize, r: usize }
+                let ((sum, _), _) = seq.prod(l..r);
+                out.push_str(&format!("{}\n", sum));
+            }
+        }
+    }
+    print!("{}", out);
+}
@@ -0,0 +1,24 @@
+use proconio::input;
+use ralgo::graph::lowlink::Lowlink;
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/two_edge_connected_components
+
+fn main() {
+    input! {
+        n: usize,
+        m: usize,
+        edges: [(usize, usize); m],
+    }
+    let ll = Lowlink::new(n, &edges);
+    let components = ll.two_edge_connected_components();
+    let mut out = String::new();
+    out.push_str(&format!("{}\n", components.len()));
+    for c in components {
+        out.push_str(&c.len().to_string());
+        for v in c {
+            out.push_str(&format!(" {}", v));
+        }
+        out.push('\n');
+    }
+    print!("{}", out);
+}
@@ -0,0 +1,33 @@
+use proconio::input;
+use ralgo::graph::two_sat::TwoSat;
+
+// verification-helper: PROBLEM https://judge.yosupo.jp/problem/two_sat
+
+fn main() {
+    input! {
+        _p: String,
+        _cnf: String,
+        n: usize,
+        m: usize,
+        clauses: [(i64, i64, i64); m],
+    }
+    let mut ts = TwoSat::new(n);
+    for (a, b, _) in clauses {
+        let literal = |x: i64| (x.unsigned_abs() as usize - 1, x > 0);
+        let (i, f) = literal(a);
+        let (j, g) = literal(b);
+        ts.add_clause(i, f, j, g);
+    }
+    match ts.solve() {
+        Some(x) => {
+            let mut out = String::from("s SATISFIABLE\nv");
+            for (i, &f) in x.iter().enumerate() {
+                let v = i as i64 + 1;
+                out.push_str(&format!(" {}", if f { v } else { -v }));
+            }
+            out.push_str(" 0");
+            println!("{}", out);
+        }
+        None => println!("s UNSATISFIABLE"),
+    }
+}